@@ -0,0 +1,308 @@
+use std::time::{Duration, Instant};
+
+use imgui::{
+    Condition, Id, StyleColor, StyleVar, TableColumnFlags, TableColumnSetup, TableFlags,
+    TableRowFlags, Ui, Window,
+};
+
+use crate::{
+    common::center,
+    data::{CompetitionData, MatchResult},
+    ProgramState,
+};
+
+// the public display for the hall, a read-only second window rotating through the views below
+
+const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.85, 0.0, 1.0];
+const BORDER_COLOR: [f32; 4] = [0.6, 0.6, 0.6, 1.0];
+const ROW_BG_COLOR: [f32; 4] = [0.15, 0.15, 0.15, 1.0];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BeamerView {
+    Standings(usize),       // the standings of a group
+    UpcomingMatches(usize), // the matches of the current batch of a group
+    Announcements,
+}
+
+pub struct BeamerState {
+    pub open: bool, // whether the public display window is requested, the window is created by the main loop
+    pub err_msg: Option<String>, // the reason why the window could not be opened
+    pub announcements: String, // free text for the announcement view, hidden if empty
+    current_view: BeamerView,
+    view_since: Instant, // the time the current view was shown first
+}
+
+impl BeamerState {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            err_msg: None,
+            announcements: String::new(),
+            current_view: BeamerView::Standings(0),
+            view_since: Instant::now(),
+        }
+    }
+
+    // shows the standings of the group right away, e.g. after new results were submitted
+    pub fn show_standings(&mut self, group_idx: usize) {
+        self.current_view = BeamerView::Standings(group_idx);
+        self.view_since = Instant::now();
+    }
+
+    // all views in the order of the rotation
+    fn views(&self, data: Option<&CompetitionData>) -> Vec<BeamerView> {
+        let mut views = vec![];
+        if let Some(data) = data.filter(|data| data.teams.is_some()) {
+            let group_count = data.teams.as_ref().unwrap().len().min(data.matches.len());
+            for group_idx in 0..group_count {
+                views.push(BeamerView::Standings(group_idx));
+                if data.current_batch[group_idx] < count_batches(data, group_idx) {
+                    views.push(BeamerView::UpcomingMatches(group_idx));
+                }
+            }
+        }
+        if !self.announcements.trim().is_empty() || views.is_empty() {
+            views.push(BeamerView::Announcements);
+        }
+        views
+    }
+
+    // switches to the next view if the current one was shown long enough or is no longer available
+    fn update_view(&mut self, data: Option<&CompetitionData>, interval_seconds: u32) {
+        let views = self.views(data);
+        match views.iter().position(|view| *view == self.current_view) {
+            Some(idx) => {
+                if self.view_since.elapsed() >= Duration::from_secs(interval_seconds.max(1) as u64)
+                {
+                    self.current_view = views[(idx + 1) % views.len()];
+                    self.view_since = Instant::now();
+                }
+            }
+            None => {
+                self.current_view = views[0];
+                self.view_since = Instant::now();
+            }
+        }
+    }
+}
+
+fn count_batches(data: &CompetitionData, group_idx: usize) -> u32 {
+    data.matches[group_idx]
+        .iter()
+        .map(|_match| _match.batch + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+// sets the high-contrast theme, called once for the imgui context of the public display
+pub fn set_beamer_style(style: &mut imgui::Style) {
+    style.use_dark_colors();
+    style.window_border_size = 0.0;
+    style.cell_padding = [16.0, 8.0];
+    style.colors[StyleColor::WindowBg as usize] = BACKGROUND_COLOR;
+    style.colors[StyleColor::Text as usize] = TEXT_COLOR;
+    style.colors[StyleColor::TableHeaderBg as usize] = BACKGROUND_COLOR;
+    style.colors[StyleColor::TableBorderStrong as usize] = BORDER_COLOR;
+    style.colors[StyleColor::TableBorderLight as usize] = BORDER_COLOR;
+    style.colors[StyleColor::TableRowBg as usize] = BACKGROUND_COLOR;
+    style.colors[StyleColor::TableRowBgAlt as usize] = ROW_BG_COLOR;
+    style.colors[StyleColor::Separator as usize] = HIGHLIGHT_COLOR;
+}
+
+// draws the public display, the texts are German like the printed documents
+pub fn draw_beamer(ui: &Ui, program_state: &mut ProgramState, size: [f32; 2]) {
+    let interval = program_state.settings.beamer_interval_seconds;
+    let beamer_state = &mut program_state.beamer_state;
+    let data = program_state.competition.data.as_ref();
+    beamer_state.update_view(data, interval);
+
+    let window_padding_token = ui.push_style_var(StyleVar::WindowPadding([40.0, 30.0]));
+    Window::new("##beamer")
+        .size(size, Condition::Always)
+        .position([0.0, 0.0], Condition::Always)
+        .no_decoration()
+        .no_inputs()
+        .build(ui, || {
+            if let Some(data) = data {
+                ui.text_colored(HIGHLIGHT_COLOR, &data.name);
+                ui.same_line();
+                let place_and_date = format!("{} - {}", data.place, data.date_string);
+                ui.set_cursor_pos([
+                    ui.window_size()[0] - ui.calc_text_size(&place_and_date)[0] - 40.0,
+                    ui.cursor_pos()[1],
+                ]);
+                ui.text(place_and_date);
+                ui.separator();
+                ui.new_line();
+            }
+
+            match (beamer_state.current_view, data) {
+                (BeamerView::Standings(group_idx), Some(data)) => {
+                    draw_standings(ui, data, group_idx)
+                }
+                (BeamerView::UpcomingMatches(group_idx), Some(data)) => {
+                    draw_upcoming_matches(ui, data, group_idx)
+                }
+                _ => draw_announcements(ui, &beamer_state.announcements),
+            }
+        });
+    window_padding_token.pop();
+}
+
+fn draw_standings(ui: &Ui, data: &CompetitionData, group_idx: usize) {
+    let status = if data.is_group_finished(group_idx) {
+        "Endstand"
+    } else {
+        "Zwischenstand"
+    };
+    center(ui, format!("{} - {status}", data.group_name(group_idx)));
+    ui.new_line();
+
+    let headers = ["Platz", "Mannschaft", "Punkte", "Quotient", "Stockpunkte"];
+    if let Some(_table_token) = ui.begin_table_with_flags(
+        "##beamer_standings",
+        headers.len(),
+        TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SIZING_FIXED_FIT,
+    ) {
+        setup_columns(ui, &headers, 1);
+
+        let teams = &data.teams.as_ref().unwrap()[group_idx];
+        data.calc_interim_result_for_group(group_idx)
+            .iter()
+            .enumerate()
+            .for_each(|(place_idx, entry)| {
+                ui.table_next_row();
+                ui.table_next_column();
+                center(ui, (place_idx + 1).to_string());
+                ui.table_next_column();
+                ui.text(&teams[entry.team_idx].name);
+                ui.table_next_column();
+                center(
+                    ui,
+                    format!("{} : {}", entry.match_points[0], entry.match_points[1]),
+                );
+                ui.table_next_column();
+                center(ui, format!("{:.3}", entry.quotient));
+                ui.table_next_column();
+                center(
+                    ui,
+                    format!("{} : {}", entry.stock_points[0], entry.stock_points[1]),
+                );
+            });
+    }
+}
+
+fn draw_upcoming_matches(ui: &Ui, data: &CompetitionData, group_idx: usize) {
+    let current_batch = data.current_batch[group_idx];
+    center(
+        ui,
+        format!(
+            "{} - Durchgang {}",
+            data.group_name(group_idx),
+            current_batch + 1
+        ),
+    );
+    if let Some(start_time) = data.batch_start_time(current_batch) {
+        center(ui, format!("Beginn {} Uhr", start_time.format("%H:%M")));
+    }
+    ui.new_line();
+
+    let mut matches: Vec<_> = data.matches[group_idx]
+        .iter()
+        .filter(|_match| _match.batch == current_batch)
+        .collect();
+    matches.sort_by_key(|_match| (_match.result == MatchResult::Break, _match.lane));
+
+    let headers = ["Bahn", "Mannschaft A", "Mannschaft B"];
+    if let Some(_table_token) = ui.begin_table_with_flags(
+        "##beamer_matches",
+        headers.len(),
+        TableFlags::BORDERS | TableFlags::ROW_BG | TableFlags::SIZING_FIXED_FIT,
+    ) {
+        setup_columns(ui, &headers, 1);
+        for _match in matches.iter() {
+            ui.table_next_row();
+            ui.table_next_column();
+            if _match.result == MatchResult::Break {
+                center(ui, "-");
+                ui.table_next_column();
+                ui.text(data.team_name(group_idx, _match.team_a));
+                ui.table_next_column();
+                ui.text_colored(HIGHLIGHT_COLOR, "Aussetzer");
+            } else {
+                center(ui, (_match.lane + 1).to_string());
+                ui.table_next_column();
+                ui.text(data.team_name(group_idx, _match.team_a));
+                ui.table_next_column();
+                ui.text(data.team_name(group_idx, _match.team_b));
+            }
+        }
+    }
+}
+
+fn draw_announcements(ui: &Ui, announcements: &str) {
+    center(ui, "Durchsagen");
+    ui.new_line();
+
+    for line in announcements.lines().filter(|line| !line.trim().is_empty()) {
+        ui.bullet();
+        ui.text_wrapped(line.trim());
+        ui.new_line();
+    }
+}
+
+// sets up the columns, the stretched column takes the remaining width, the others fit their content
+fn setup_columns(ui: &Ui, headers: &[&str], stretch_column: usize) {
+    headers.iter().enumerate().for_each(|(idx, header)| {
+        ui.table_setup_column_with(TableColumnSetup {
+            name: *header,
+            flags: if idx == stretch_column {
+                TableColumnFlags::WIDTH_STRETCH
+            } else {
+                TableColumnFlags::WIDTH_FIXED
+            },
+            init_width_or_weight: if idx == stretch_column {
+                0.0
+            } else {
+                ui.calc_text_size(header)[0] * 1.5
+            },
+            user_id: Id::Int(0),
+        });
+    });
+
+    ui.table_next_row_with_flags(TableRowFlags::HEADERS);
+    headers.iter().for_each(|header| {
+        ui.table_next_column();
+        let text_color = ui.push_style_color(StyleColor::Text, HIGHLIGHT_COLOR);
+        center(ui, header);
+        text_color.pop();
+    });
+}
+
+#[cfg(test)]
+#[test]
+fn test_beamer_views() {
+    let mut beamer_state = BeamerState::new();
+    assert!(beamer_state.views(None) == vec![BeamerView::Announcements]);
+
+    let mut data = crate::data::two_team_competition();
+    assert!(
+        beamer_state.views(Some(&data))
+            == vec![BeamerView::Standings(0), BeamerView::UpcomingMatches(0)]
+    );
+
+    // the upcoming matches are left out after the last batch, the announcements are shown if entered
+    data.current_batch = vec![1];
+    beamer_state.announcements = String::from("Siegerehrung um 17 Uhr");
+    assert!(
+        beamer_state.views(Some(&data))
+            == vec![BeamerView::Standings(0), BeamerView::Announcements]
+    );
+
+    // an unavailable view is replaced by the first one
+    beamer_state.show_standings(3);
+    beamer_state.update_view(Some(&data), 15);
+    assert!(beamer_state.current_view == BeamerView::Standings(0));
+}
@@ -0,0 +1,232 @@
+use std::{
+    fs::{self, File},
+    io::{Cursor, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use super::journal::journal_path;
+
+#[cfg(test)]
+use super::{
+    read_write::{read_from_file, save_to_file},
+    CompetitionData,
+};
+
+// An .isra archive is a zip file bundling everything belonging to a competition:
+// the competition data, the exported documents, the logos and the audit trail.
+pub const ARCHIVE_EXTENSION: &str = "isra";
+
+const COMPETITION_ENTRY: &str = "competition.json";
+const JOURNAL_ENTRY: &str = "journal.jsonl";
+const BUNDLED_DIRS: [&str; 2] = ["exports", "logos"]; // folders next to the competition file which are bundled
+
+pub fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |ext| ext == ARCHIVE_EXTENSION)
+}
+
+// creates the content of the archive at file_path, the bundled files are taken from the folder of the archive
+pub fn build_archive(file_path: &Path, json: &str) -> Result<Vec<u8>, String> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    add_entry(&mut writer, options, COMPETITION_ENTRY, json.as_bytes())?;
+
+    let journal_path = journal_path(file_path);
+    if journal_path.is_file() {
+        add_file(&mut writer, options, JOURNAL_ENTRY, &journal_path)?;
+    }
+
+    let dir_path = match file_path.parent() {
+        Some(dir_path) => dir_path,
+        None => {
+            return Err(String::from(
+                "Could not retrieve the folder of the archive!",
+            ))
+        }
+    };
+
+    for dir_name in BUNDLED_DIRS {
+        let entries = match fs::read_dir(dir_path.join(dir_name)) {
+            Ok(entries) => entries,
+            Err(_) => continue, // the folder does not exist yet
+        };
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            add_file(
+                &mut writer,
+                options,
+                &format!("{dir_name}/{file_name}"),
+                &path,
+            )?;
+        }
+    }
+
+    match writer.finish() {
+        Ok(cursor) => Ok(cursor.into_inner()),
+        Err(_) => Err(String::from("Finishing the archive failed!")),
+    }
+}
+
+fn add_entry(
+    writer: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: FileOptions,
+    name: &str,
+    content: &[u8],
+) -> Result<(), String> {
+    if writer.start_file(name, options).is_err() || writer.write_all(content).is_err() {
+        return Err(format!("Adding {name} to the archive failed!"));
+    }
+    Ok(())
+}
+
+fn add_file(
+    writer: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: FileOptions,
+    name: &str,
+    path: &Path,
+) -> Result<(), String> {
+    match fs::read(path) {
+        Ok(content) => add_entry(writer, options, name, &content),
+        Err(_) => Err(format!("Error whilst reading file: {}", path.display())),
+    }
+}
+
+fn open_archive(file_path: &Path) -> Result<ZipArchive<File>, String> {
+    let file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(_) => {
+            return Err(format!(
+                "Error whilst reading file: {}",
+                file_path.display()
+            ))
+        }
+    };
+    match ZipArchive::new(file) {
+        Ok(archive) => Ok(archive),
+        Err(_) => Err(format!(
+            "The file is no valid ISRAT archive: {}",
+            file_path.display()
+        )),
+    }
+}
+
+// reads the competition data in its json representation from the archive
+pub fn read_archive_json(file_path: &Path) -> Result<String, String> {
+    let mut archive = open_archive(file_path)?;
+    let mut entry = match archive.by_name(COMPETITION_ENTRY) {
+        Ok(entry) => entry,
+        Err(_) => {
+            return Err(String::from(
+                "The archive does not contain competition data!",
+            ))
+        }
+    };
+
+    let mut json_string = String::new();
+    match entry.read_to_string(&mut json_string) {
+        Ok(_) => Ok(json_string),
+        Err(_) => Err(String::from(
+            "Reading the competition data from the archive failed!",
+        )),
+    }
+}
+
+// extracts the bundled files next to the archive, files which already exist are kept
+pub fn extract_archive_files(file_path: &Path) -> Result<(), String> {
+    let dir_path = match file_path.parent() {
+        Some(dir_path) => dir_path,
+        None => {
+            return Err(String::from(
+                "Could not retrieve the folder of the archive!",
+            ))
+        }
+    };
+    let mut archive = open_archive(file_path)?;
+
+    for idx in 0..archive.len() {
+        let mut entry = match archive.by_index(idx) {
+            Ok(entry) => entry,
+            Err(_) => return Err(String::from("The archive is damaged!")),
+        };
+        if !entry.is_file() {
+            continue;
+        }
+
+        // only extract the known entries, never write outside of the bundled folders
+        let target_path = match entry.enclosed_name() {
+            Some(name) if name == Path::new(JOURNAL_ENTRY) => journal_path(file_path),
+            Some(name) => {
+                let components: Vec<Component> = name.components().collect();
+                match components.as_slice() {
+                    [Component::Normal(dir_name), Component::Normal(file_name)]
+                        if BUNDLED_DIRS
+                            .iter()
+                            .any(|bundled| dir_name.to_str() == Some(*bundled)) =>
+                    {
+                        dir_path.join(dir_name).join(file_name)
+                    }
+                    _ => continue,
+                }
+            }
+            None => continue,
+        };
+
+        if target_path.exists() {
+            continue;
+        }
+        if let Some(parent) = target_path.parent() {
+            if fs::create_dir_all(parent).is_err() {
+                return Err(format!("Creation of folder {} failed!", parent.display()));
+            }
+        }
+
+        let mut content = Vec::new();
+        if entry.read_to_end(&mut content).is_err() || fs::write(&target_path, content).is_err() {
+            return Err(format!(
+                "Extracting {} from the archive failed!",
+                target_path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_archive_round_trip() {
+    let source_dir = PathBuf::from("./tmp/documents/archive_test/source");
+    let target_dir = PathBuf::from("./tmp/documents/archive_test/target");
+    let _ = fs::remove_dir_all(source_dir.parent().unwrap());
+    fs::create_dir_all(source_dir.join("exports")).unwrap();
+    fs::write(source_dir.join("exports").join("result.pdf"), b"%PDF-1.5").unwrap();
+
+    let mut data = CompetitionData::empty();
+    data.name = String::from("Archivmeisterschaft");
+
+    let archive_path = source_dir.join("competition.isra");
+    fs::write(journal_path(&archive_path), "{}\n").unwrap();
+    assert!(save_to_file(archive_path.clone(), &data, 0).is_ok());
+
+    // a handed over archive contains everything needed to continue the competition
+    fs::create_dir_all(&target_dir).unwrap();
+    let copied_path = target_dir.join("competition.isra");
+    fs::copy(&archive_path, &copied_path).unwrap();
+
+    assert_eq!(read_from_file(copied_path.clone()).unwrap().name, data.name);
+    assert!(extract_archive_files(&copied_path).is_ok());
+    assert_eq!(
+        fs::read(target_dir.join("exports").join("result.pdf")).unwrap(),
+        b"%PDF-1.5"
+    );
+    assert!(journal_path(&copied_path).is_file());
+}
@@ -0,0 +1,419 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{settings::config_dir, ProgramStage, ProgramState};
+
+use super::{
+    read_write::{read_from_file, save_to_file},
+    Competition, CompetitionData, MatchResult,
+};
+
+// the state of the autosave, which is driven by the main loop
+pub struct AutosaveState {
+    interval_start: Instant,      // the start of the current autosave interval
+    last_saved_hash: Option<u64>, // hash of the data written by the last autosave, used to detect changes
+    session_file: Option<(PathBuf, Option<PathBuf>)>, // the autosave file written in this session and the competition file it belongs to
+    pub last_autosave: Option<DateTime<Local>>,
+    pub last_error: Option<String>, // the error message of the last autosave, None if it succeeded
+}
+
+impl AutosaveState {
+    pub fn new() -> Self {
+        AutosaveState {
+            interval_start: Instant::now(),
+            last_saved_hash: None,
+            session_file: None,
+            last_autosave: None,
+            last_error: None,
+        }
+    }
+
+    // the hash of the competition data if it changed since the last save or autosave, None if there is nothing to save
+    fn changed_data_hash(&self, competition: &Competition) -> Option<u64> {
+        let data_hash = competition.data_hash()?;
+        if competition.saved_data_hash == Some(data_hash) || self.last_saved_hash == Some(data_hash)
+        {
+            return None;
+        }
+        Some(data_hash)
+    }
+}
+
+// stored next to each autosave file to be able to assign it to its competition file after a crash
+#[derive(Serialize, Deserialize)]
+struct AutosaveInfo {
+    source_file: Option<PathBuf>, // None if the competition was never saved
+}
+
+// an autosave which is newer than the last regular save of its competition
+pub struct RecoverableAutosave {
+    pub autosave_path: PathBuf,
+    pub source_file: Option<PathBuf>,
+    pub autosave_time: DateTime<Local>,
+    pub autosave_data: CompetitionData,
+    pub saved_data: Option<CompetitionData>, // the content of the competition file, if it exists
+}
+
+impl RecoverableAutosave {
+    // rows of (label, value in the autosave, value in the competition file) to preview the differences
+    pub fn preview_rows(&self) -> Vec<(String, String, String)> {
+        let saved_value = |value: &dyn Fn(&CompetitionData) -> String| match &self.saved_data {
+            Some(saved_data) => value(saved_data),
+            None => String::from("-"),
+        };
+
+        let mut rows = vec![
+            (
+                String::from("Name"),
+                self.autosave_data.name.clone(),
+                saved_value(&|data| data.name.clone()),
+            ),
+            (
+                String::from("Teams"),
+                count_teams(&self.autosave_data).to_string(),
+                saved_value(&|data| count_teams(data).to_string()),
+            ),
+            (
+                String::from("Played matches"),
+                count_played_matches(&self.autosave_data).to_string(),
+                saved_value(&|data| count_played_matches(data).to_string()),
+            ),
+        ];
+
+        // the current batch of each group shows how far the competition has progressed
+        (0..self.autosave_data.current_batch.len()).for_each(|group_idx| {
+            let group_name = match self.autosave_data.group_names.as_ref() {
+                Some(group_names) if group_idx < group_names.len() => {
+                    group_names[group_idx].clone()
+                }
+                _ => format!("Group {}", group_idx + 1),
+            };
+            rows.push((
+                format!("Batch {group_name}"),
+                format_batch(&self.autosave_data, group_idx),
+                saved_value(&|data| format_batch(data, group_idx)),
+            ));
+        });
+
+        rows
+    }
+}
+
+fn count_teams(data: &CompetitionData) -> usize {
+    match data.teams.as_ref() {
+        Some(teams) => teams.iter().map(|group| group.len()).sum(),
+        None => 0,
+    }
+}
+
+fn count_played_matches(data: &CompetitionData) -> usize {
+    data.matches
+        .iter()
+        .flatten()
+        .filter(|_match| {
+            _match.result != MatchResult::NotPlayed && _match.result != MatchResult::Break
+        })
+        .count()
+}
+
+fn format_batch(data: &CompetitionData, group_idx: usize) -> String {
+    match data.current_batch.get(group_idx) {
+        Some(batch) => (batch + 1).to_string(),
+        None => String::from("-"),
+    }
+}
+
+// called every frame, saves the competition data if the interval elapsed and the data changed since the last save
+pub fn check_autosave(program_state: &mut ProgramState) {
+    let interval_minutes = program_state.settings.autosave_interval_minutes;
+    if interval_minutes == 0 {
+        return;
+    }
+
+    let autosave_state = &mut program_state.autosave_state;
+    if autosave_state.interval_start.elapsed() < Duration::from_secs(interval_minutes as u64 * 60) {
+        return;
+    }
+    autosave_state.interval_start = Instant::now();
+
+    let data_hash = match autosave_state.changed_data_hash(&program_state.competition) {
+        Some(data_hash) => data_hash,
+        None => return,
+    };
+
+    let save_res = write_autosave(program_state);
+
+    let autosave_state = &mut program_state.autosave_state;
+    match save_res {
+        Ok(_) => {
+            autosave_state.last_saved_hash = Some(data_hash);
+            autosave_state.last_autosave = Some(Local::now());
+            autosave_state.last_error = None;
+        }
+        Err(msg) => {
+            eprintln!("[check_autosave]: Autosave failed: {msg}");
+            autosave_state.last_error = Some(msg);
+        }
+    }
+}
+
+// writes the competition data to the autosave file of this session, the competition file itself is never touched
+fn write_autosave(program_state: &mut ProgramState) -> Result<(), String> {
+    let data = match program_state.competition.data.as_ref() {
+        Some(data) => data,
+        None => return Err(String::from("There is no competition data to autosave!")),
+    };
+    let source_file = program_state.competition.absolute_file_path.clone();
+
+    // start a new autosave file if another competition file is edited now
+    let autosave_path = match program_state.autosave_state.session_file.as_ref() {
+        Some((autosave_path, session_source)) if *session_source == source_file => {
+            autosave_path.clone()
+        }
+        previous => {
+            // the autosave of a never saved competition is obsolete as soon as it got saved
+            if let Some((previous_path, None)) = previous {
+                if program_state.competition.saved_data_hash.is_some() {
+                    remove_autosave(previous_path);
+                }
+            }
+            new_autosave_path(data)
+        }
+    };
+
+    save_to_file(autosave_path.clone(), data, 0)?;
+
+    let info = AutosaveInfo {
+        source_file: source_file.clone(),
+    };
+    let info_json = match serde_json::to_string_pretty(&info) {
+        Ok(info_json) => info_json,
+        Err(_) => return Err(String::from("Serialization of the autosave info failed!")),
+    };
+    if fs::write(autosave_path.with_extension("info"), info_json).is_err() {
+        return Err(String::from("Write to autosave info file failed!"));
+    }
+
+    program_state.autosave_state.session_file = Some((autosave_path, source_file));
+    Ok(())
+}
+
+// the folder where the autosaves of all competitions are stored
+fn autosave_dir() -> PathBuf {
+    match config_dir() {
+        Some(config_dir) => config_dir.join("autosaves"),
+        None => std::env::temp_dir().join("israt").join("autosaves"),
+    }
+}
+
+fn new_autosave_path(data: &CompetitionData) -> PathBuf {
+    let sanitized_competition_name = data.name.replace(&['/', '\\', '%', '.', '~'], "");
+    let autosave_file_name = format!(
+        "{sanitized_competition_name}-{}.json",
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    autosave_dir().join(autosave_file_name)
+}
+
+fn remove_autosave(autosave_path: &Path) {
+    let _ = fs::remove_file(autosave_path);
+    let _ = fs::remove_file(autosave_path.with_extension("info"));
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+// searches the autosave folder for autosaves newer than the last regular save of their competition
+// and removes autosaves older than the retention time, 0 days keeps them forever
+pub fn find_recoverable_autosaves(retention_days: u32) -> Vec<RecoverableAutosave> {
+    find_recoverable_autosaves_in(&autosave_dir(), retention_days)
+}
+
+fn find_recoverable_autosaves_in(
+    autosave_dir: &Path,
+    retention_days: u32,
+) -> Vec<RecoverableAutosave> {
+    let entries = match fs::read_dir(autosave_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    let retention = Duration::from_secs(retention_days as u64 * 24 * 60 * 60);
+    let mut autosaves: Vec<RecoverableAutosave> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+        .filter_map(|autosave_path| {
+            let autosave_modified = modified_time(&autosave_path)?;
+            let age = SystemTime::now()
+                .duration_since(autosave_modified)
+                .unwrap_or_default();
+            if retention_days > 0 && age > retention {
+                remove_autosave(&autosave_path);
+                return None;
+            }
+
+            // autosaves without info file are treated like autosaves of never saved competitions
+            let source_file = fs::read_to_string(autosave_path.with_extension("info"))
+                .ok()
+                .and_then(|info_json| serde_json::from_str::<AutosaveInfo>(&info_json).ok())
+                .and_then(|info| info.source_file);
+
+            if let Some(source_file) = source_file.as_ref() {
+                match modified_time(source_file) {
+                    Some(source_modified) if source_modified >= autosave_modified => return None,
+                    _ => (),
+                }
+            }
+
+            let autosave_data = match read_from_file(autosave_path.clone()) {
+                Ok(autosave_data) => autosave_data,
+                Err(msg) => {
+                    eprintln!(
+                        "[find_recoverable_autosaves]: Cannot read autosave {}: {msg}",
+                        autosave_path.display()
+                    );
+                    return None;
+                }
+            };
+            let saved_data = source_file
+                .as_ref()
+                .and_then(|source_file| read_from_file(source_file.clone()).ok());
+
+            Some(RecoverableAutosave {
+                autosave_path,
+                source_file,
+                autosave_time: DateTime::<Local>::from(autosave_modified),
+                autosave_data,
+                saved_data,
+            })
+        })
+        .collect();
+
+    // the newest first
+    autosaves.sort_by(|a, b| b.autosave_time.cmp(&a.autosave_time));
+    autosaves
+}
+
+// called at startup, opens the recovery window if there are autosaves which can be restored
+pub fn check_for_recoverable_autosaves(program_state: &mut ProgramState) {
+    let autosaves = find_recoverable_autosaves(program_state.settings.autosave_retention_days);
+    let menu_bar_state = &mut program_state.main_menu_bar_state;
+    menu_bar_state.recovery_popup = !autosaves.is_empty();
+    menu_bar_state.recovery_list = autosaves;
+}
+
+// replaces the competition data by the autosave, which is written back to the competition file with the next save
+pub fn restore_autosave(program_state: &mut ProgramState, autosave: RecoverableAutosave) {
+    let competition = &mut program_state.competition;
+    competition.data = Some(autosave.autosave_data);
+    competition.absolute_dir_path = autosave
+        .source_file
+        .as_ref()
+        .and_then(|source_file| source_file.parent().map(|parent| parent.to_path_buf()));
+    competition.absolute_file_path = autosave.source_file.clone();
+    competition.saved_data_hash = None;
+
+    // the journal continues from the restored state
+    if let Err(msg) = competition.record_journal_snapshot() {
+        eprintln!("[restore_autosave]: {msg}");
+    }
+
+    // continue writing to the restored autosave, so it is kept until the data is saved
+    program_state.autosave_state.session_file =
+        Some((autosave.autosave_path, autosave.source_file));
+    program_state.autosave_state.last_saved_hash = None;
+
+    // the group count may differ, therefore reset the erg screen completely
+    program_state.erg_screen_state = None;
+    program_state.new_screen_state = None;
+    program_state.switch_to_stage(ProgramStage::CurrentErgViewStage);
+}
+
+pub fn discard_autosave(autosave: &RecoverableAutosave) {
+    remove_autosave(&autosave.autosave_path);
+}
+
+#[cfg(test)]
+#[test]
+fn test_autosave() {
+    use super::two_team_competition;
+    use std::fs::File;
+
+    // the autosave is skipped if the data did not change since the last save or autosave
+    let mut competition = Competition::empty();
+    let mut autosave_state = AutosaveState::new();
+    assert_eq!(autosave_state.changed_data_hash(&competition), None);
+    competition.data = Some(two_team_competition());
+    competition.saved_data_hash = competition.data_hash();
+    assert_eq!(autosave_state.changed_data_hash(&competition), None);
+    competition.data.as_mut().unwrap().name = String::from("Autosavemeisterschaft");
+    let data_hash = autosave_state.changed_data_hash(&competition);
+    assert!(data_hash.is_some());
+    autosave_state.last_saved_hash = data_hash;
+    assert_eq!(autosave_state.changed_data_hash(&competition), None);
+
+    let temp_config_dir =
+        std::env::temp_dir().join(format!("israt-autosaves-{}", std::process::id()));
+    let autosave_dir = temp_config_dir.join("autosaves");
+    let _ = fs::remove_dir_all(&temp_config_dir);
+    fs::create_dir_all(&autosave_dir).unwrap();
+
+    let now = SystemTime::now();
+    let write_save = |path: PathBuf, hours_ago: u64| {
+        save_to_file(path.clone(), competition.data.as_ref().unwrap(), 0).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(now - Duration::from_secs(hours_ago * 60 * 60))
+            .unwrap();
+        path
+    };
+    let write_autosave = |file_name: &str, source_file: Option<&PathBuf>, hours_ago: u64| {
+        let info = AutosaveInfo {
+            source_file: source_file.cloned(),
+        };
+        let path = autosave_dir.join(file_name);
+        fs::write(
+            path.with_extension("info"),
+            serde_json::to_string(&info).unwrap(),
+        )
+        .unwrap();
+        write_save(path, hours_ago)
+    };
+
+    // the competition file was saved 2 hours ago
+    let source_file = write_save(temp_config_dir.join("competition.json"), 2);
+    let newer = write_autosave("newer.json", Some(&source_file), 1);
+    let older = write_autosave("older.json", Some(&source_file), 3);
+    let unsaved = write_autosave("unsaved.json", None, 0);
+    let expired = write_autosave("expired.json", None, 10 * 24);
+
+    // only autosaves newer than their competition file are offered, the newest first
+    let autosaves = find_recoverable_autosaves_in(&autosave_dir, 0);
+    let autosave_paths: Vec<&PathBuf> = autosaves
+        .iter()
+        .map(|autosave| &autosave.autosave_path)
+        .collect();
+    assert_eq!(autosave_paths, vec![&unsaved, &newer, &expired]);
+    assert!(autosaves[1].saved_data.is_some());
+    assert!(autosaves[0].source_file.is_none());
+
+    // autosaves older than the retention time are removed with their info file
+    let autosaves = find_recoverable_autosaves_in(&autosave_dir, 7);
+    assert_eq!(autosaves.len(), 2);
+    assert!(!expired.exists());
+    assert!(!expired.with_extension("info").exists());
+    assert!(older.exists());
+
+    fs::remove_dir_all(&temp_config_dir).unwrap();
+}
@@ -0,0 +1,209 @@
+use csv::Writer;
+
+use super::{CompetitionData, MatchResult};
+
+// CSV exports of the raw competition data, e.g. for statistics of the federation
+
+fn finish_csv(writer: Writer<Vec<u8>>) -> Result<String, String> {
+    match writer.into_inner() {
+        Ok(bytes) => String::from_utf8(bytes).map_err(|_| String::from("Invalid CSV content!")),
+        Err(_) => Err(String::from("Writing the CSV content failed!")),
+    }
+}
+
+fn write_record<const N: usize>(
+    writer: &mut Writer<Vec<u8>>,
+    record: [String; N],
+) -> Result<(), String> {
+    writer
+        .write_record(&record)
+        .map_err(|err| format!("Writing the CSV content failed: {err}"))
+}
+
+impl CompetitionData {
+    pub fn group_name(&self, group_idx: usize) -> String {
+        match self.group_names.as_ref() {
+            Some(group_names) if group_idx < group_names.len() => group_names[group_idx].clone(),
+            _ => format!("Group {}", group_idx + 1),
+        }
+    }
+
+    pub fn team_name(&self, group_idx: usize, team_idx: usize) -> String {
+        self.teams.as_ref().unwrap()[group_idx][team_idx]
+            .name
+            .clone()
+    }
+
+    // a group is finished if every match except the breaks has a result
+    pub fn is_group_finished(&self, group_idx: usize) -> bool {
+        self.matches[group_idx]
+            .iter()
+            .all(|_match| _match.result != MatchResult::NotPlayed)
+    }
+
+    // the standings of all groups, the status column tells whether the standing is final or interim
+    pub fn get_standings_as_csv(&self) -> Result<String, String> {
+        let mut writer = Writer::from_writer(vec![]);
+        write_record(
+            &mut writer,
+            [
+                "group",
+                "status",
+                "place",
+                "team",
+                "region",
+                "match_points_won",
+                "match_points_lost",
+                "quotient",
+                "stock_points_won",
+                "stock_points_lost",
+            ]
+            .map(String::from),
+        )?;
+
+        for group_idx in 0..self.teams.as_ref().unwrap().len() {
+            let status = if self.is_group_finished(group_idx) {
+                "final"
+            } else {
+                "interim"
+            };
+
+            for (place_idx, entry) in self
+                .calc_interim_result_for_group(group_idx)
+                .iter()
+                .enumerate()
+            {
+                let team = &self.teams.as_ref().unwrap()[group_idx][entry.team_idx];
+                write_record(
+                    &mut writer,
+                    [
+                        self.group_name(group_idx),
+                        String::from(status),
+                        (place_idx + 1).to_string(),
+                        team.name.clone(),
+                        team.region.clone(),
+                        entry.match_points[0].to_string(),
+                        entry.match_points[1].to_string(),
+                        format!("{:.3}", entry.quotient),
+                        entry.stock_points[0].to_string(),
+                        entry.stock_points[1].to_string(),
+                    ],
+                )?;
+            }
+        }
+
+        finish_csv(writer)
+    }
+
+    // the complete schedule including the breaks, ordered by group, batch and lane
+    pub fn get_schedule_as_csv(&self) -> Result<String, String> {
+        let mut writer = Writer::from_writer(vec![]);
+        write_record(
+            &mut writer,
+            ["group", "batch", "lane", "type", "team_a", "team_b"].map(String::from),
+        )?;
+
+        for (group_idx, matches) in self.matches.iter().enumerate() {
+            let mut matches: Vec<_> = matches.iter().collect();
+            matches.sort_by_key(|_match| (_match.batch, _match.lane));
+
+            for _match in matches {
+                let is_break = _match.result == MatchResult::Break;
+                write_record(
+                    &mut writer,
+                    [
+                        self.group_name(group_idx),
+                        (_match.batch + 1).to_string(),
+                        if is_break {
+                            String::new()
+                        } else {
+                            (_match.lane + 1).to_string()
+                        },
+                        String::from(if is_break { "break" } else { "match" }),
+                        self.team_name(group_idx, _match.team_a),
+                        if is_break {
+                            String::new()
+                        } else {
+                            self.team_name(group_idx, _match.team_b)
+                        },
+                    ],
+                )?;
+            }
+        }
+
+        finish_csv(writer)
+    }
+
+    // all entered match results, ordered by group, batch and lane
+    pub fn get_results_as_csv(&self) -> Result<String, String> {
+        let mut writer = Writer::from_writer(vec![]);
+        write_record(
+            &mut writer,
+            [
+                "group", "batch", "lane", "team_a", "team_b", "points_a", "points_b", "result",
+            ]
+            .map(String::from),
+        )?;
+
+        for (group_idx, matches) in self.matches.iter().enumerate() {
+            let mut matches: Vec<_> = matches
+                .iter()
+                .filter(|_match| _match.points.is_some())
+                .collect();
+            matches.sort_by_key(|_match| (_match.batch, _match.lane));
+
+            for _match in matches {
+                let points = _match.points.unwrap();
+                write_record(
+                    &mut writer,
+                    [
+                        self.group_name(group_idx),
+                        (_match.batch + 1).to_string(),
+                        (_match.lane + 1).to_string(),
+                        self.team_name(group_idx, _match.team_a),
+                        self.team_name(group_idx, _match.team_b),
+                        points[0].to_string(),
+                        points[1].to_string(),
+                        _match.result.to_string(),
+                    ],
+                )?;
+            }
+        }
+
+        finish_csv(writer)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_csv_export() {
+    use super::Match;
+
+    let mut data = super::two_team_competition();
+    data.teams.as_mut().unwrap()[0][0].name = String::from("SV \"Alle Neun\", Musterstadt");
+    data.matches[0][0].set_points([7, 3]);
+    data.matches[0].push(Match {
+        team_a: 1,
+        team_b: 0,
+        points: None,
+        result: MatchResult::NotPlayed,
+        batch: 1,
+        lane: 0,
+    });
+    data.current_batch = vec![1];
+
+    let standings = data.get_standings_as_csv().unwrap();
+    let mut lines = standings.lines();
+    assert!(lines.next().unwrap().starts_with("group,status,place,team"));
+    // names with quotes and commas are quoted
+    assert_eq!(
+        lines.next().unwrap(),
+        r#"Gruppe A,interim,1,"SV ""Alle Neun"", Musterstadt",Kreis 1,2,0,2.333,7,3"#
+    );
+
+    assert_eq!(data.get_schedule_as_csv().unwrap().lines().count(), 3);
+
+    let results = data.get_results_as_csv().unwrap();
+    assert_eq!(results.lines().count(), 2);
+    assert!(results.ends_with("TSV Beispiel,7,3,WinnerA\n"));
+}
@@ -0,0 +1,511 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use tectonic::config::PersistentConfig;
+use tectonic::driver::{OutputFormat, ProcessingSessionBuilder};
+use tectonic::status::NoopStatusBackend;
+
+// The PDF exports run in their own threads, each export is a job reporting its progress and result back
+// to the GUI via a channel.
+
+// the source of the TeX resources (packages, format files) needed by the LaTeX engine
+#[derive(Clone)]
+pub enum TexBundle {
+    Online,         // the default bundle of Tectonic, downloaded files are cached
+    Cached,         // the default bundle, but only the cached files, for halls without internet
+    Local(PathBuf), // a bundle as zip file or directory, e.g. copied from a USB stick
+}
+
+pub enum ExportJobStatus {
+    Running(String),           // the current step
+    Finished(Option<PathBuf>), // the written file, None for jobs without output file
+    Failed(String),
+    Cancelled,
+}
+
+// sent from the export thread to the GUI
+enum ExportJobMessage {
+    Progress(String),
+    Finished(Option<PathBuf>),
+    Failed(String),
+}
+
+pub struct ExportJob {
+    pub name: String, // the file name of the document without extension
+    pub status: ExportJobStatus,
+    receiver: Receiver<ExportJobMessage>,
+    cancel: Arc<AtomicBool>, // checked by the export thread between its steps
+}
+
+impl ExportJob {
+    pub fn is_running(&self) -> bool {
+        matches!(self.status, ExportJobStatus::Running(_))
+    }
+
+    // the LaTeX engine can not be interrupted, a cancelled job stops after the current step and removes its output
+    pub fn cancel(&mut self) {
+        if self.is_running() {
+            self.cancel.store(true, Ordering::Relaxed);
+            self.status = ExportJobStatus::Cancelled;
+        }
+    }
+
+    // applies all messages sent by the export thread since the last call
+    fn check_messages(&mut self) {
+        while self.is_running() {
+            self.status = match self.receiver.try_recv() {
+                Ok(ExportJobMessage::Progress(step)) => ExportJobStatus::Running(step),
+                Ok(ExportJobMessage::Finished(path)) => ExportJobStatus::Finished(path),
+                Ok(ExportJobMessage::Failed(msg)) => ExportJobStatus::Failed(msg),
+                Err(TryRecvError::Empty) => return,
+                // the thread ended without a result, e.g. it panicked
+                Err(TryRecvError::Disconnected) => {
+                    ExportJobStatus::Failed(String::from("The export stopped unexpectedly!"))
+                }
+            };
+        }
+    }
+}
+
+pub struct ExportJobs {
+    pub jobs: Vec<ExportJob>, // the oldest first
+    started: bool,            // whether a job was started since the last call of take_started
+    count_pdf_exports: u32,   // numbers the working directories of the PDF exports
+}
+
+impl ExportJobs {
+    pub fn new() -> Self {
+        Self {
+            jobs: vec![],
+            started: false,
+            count_pdf_exports: 0,
+        }
+    }
+
+    // renders the LaTeX document to export_dir_path/name.pdf
+    pub fn start_pdf_export(
+        &mut self,
+        name: String,
+        export_dir_path: PathBuf,
+        latex: String,
+        tex_bundle: TexBundle,
+    ) {
+        // jobs of the same document started within a minute get the same name, but not the same working directory
+        let work_dir_path = export_dir_path.join(format!(
+            ".export-{}-{}",
+            std::process::id(),
+            self.count_pdf_exports
+        ));
+        self.count_pdf_exports += 1;
+
+        let thread_name = name.clone();
+        self.start_job(name, move |tx, cancel| {
+            export_pdf(
+                &thread_name,
+                &export_dir_path,
+                &work_dir_path,
+                &latex,
+                &tex_bundle,
+                tx,
+                cancel,
+            )
+            .map(|path_opt| path_opt.map(Some))
+        });
+    }
+
+    // renders the given documents once, so that all TeX resources they need are in the cache of the online
+    // bundle or are verified to be in the local bundle
+    pub fn start_offline_preparation(
+        &mut self,
+        documents: Vec<(String, String)>,
+        work_dir_path: PathBuf,
+        tex_bundle: TexBundle,
+    ) {
+        self.start_job(String::from("Offline TeX resources"), move |tx, cancel| {
+            prepare_offline_exports(&documents, &work_dir_path, &tex_bundle, tx, cancel)
+                .map(|done| done.map(|_| None))
+        });
+    }
+
+    // runs the job in its own thread, the job returns its result or None if it was cancelled
+    fn start_job<F>(&mut self, name: String, job: F)
+    where
+        F: FnOnce(
+                &Sender<ExportJobMessage>,
+                &AtomicBool,
+            ) -> Result<Option<Option<PathBuf>>, String>
+            + Send
+            + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let thread_cancel = cancel.clone();
+        let spawn_res = thread::Builder::new()
+            .name(String::from("Export job thread"))
+            .spawn(move || {
+                let message = match job(&tx, &thread_cancel) {
+                    Ok(Some(path_opt)) => ExportJobMessage::Finished(path_opt),
+                    Ok(None) => return, // cancelled, the GUI already knows
+                    Err(msg) => ExportJobMessage::Failed(msg),
+                };
+                // the GUI may have dropped the job meanwhile
+                let _ = tx.send(message);
+            });
+
+        let status = match spawn_res {
+            Ok(_) => ExportJobStatus::Running(String::from("Starting")),
+            Err(_) => ExportJobStatus::Failed(String::from("Starting the export failed!")),
+        };
+        self.jobs.push(ExportJob {
+            name,
+            status,
+            receiver: rx,
+            cancel,
+        });
+        self.started = true;
+    }
+
+    pub fn check_messages(&mut self) {
+        self.jobs.iter_mut().for_each(|job| job.check_messages());
+    }
+
+    // whether a job was started since the last call, the job list is shown then
+    pub fn take_started(&mut self) -> bool {
+        std::mem::take(&mut self.started)
+    }
+
+    pub fn cancel_all(&mut self) {
+        self.jobs.iter_mut().for_each(|job| job.cancel());
+    }
+
+    // removes the jobs which are no longer running
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|job| job.is_running());
+    }
+}
+
+// returns the path of the PDF or None if the job was cancelled, the PDF is rendered in the working directory of the
+// job and moved to the export directory when done, so that a cancelled job never removes the PDF of another job
+fn export_pdf(
+    name: &str,
+    export_dir_path: &Path,
+    work_dir_path: &Path,
+    latex: &str,
+    tex_bundle: &TexBundle,
+    tx: &Sender<ExportJobMessage>,
+    cancel: &AtomicBool,
+) -> Result<Option<PathBuf>, String> {
+    if !export_dir_path.exists() {
+        if fs::create_dir(export_dir_path).is_err() {
+            return Err(String::from("Creation of the export directory failed!"));
+        }
+    } else if !export_dir_path.is_dir() {
+        return Err(String::from(r#""exports" exists, but is no directory!"#));
+    }
+
+    if fs::create_dir_all(work_dir_path).is_err() {
+        return Err(String::from("Creation of the working directory failed!"));
+    }
+
+    let progress = |step: &str| {
+        let _ = tx.send(ExportJobMessage::Progress(String::from(step)));
+        !cancel.load(Ordering::Relaxed)
+    };
+    let res = match run_latex(name, work_dir_path, latex, tex_bundle, &progress) {
+        Ok(Some(work_pdf_path)) if !cancel.load(Ordering::Relaxed) => {
+            let pdf_path = export_dir_path.join(format!("{name}.pdf"));
+            match fs::rename(work_pdf_path, &pdf_path) {
+                Ok(_) => Ok(Some(pdf_path)),
+                Err(_) => Err(format!("Moving {name}.pdf to the export directory failed!")),
+            }
+        }
+        Ok(_) => Ok(None),
+        Err(msg) => Err(msg),
+    };
+
+    let _ = fs::remove_dir_all(work_dir_path);
+    res
+}
+
+// the online bundle fetches the missing files in a first pass, the second pass checks that the cache suffices
+fn prepare_offline_exports(
+    documents: &[(String, String)],
+    work_dir_path: &Path,
+    tex_bundle: &TexBundle,
+    tx: &Sender<ExportJobMessage>,
+    cancel: &AtomicBool,
+) -> Result<Option<()>, String> {
+    let passes = match tex_bundle {
+        TexBundle::Online | TexBundle::Cached => {
+            vec![
+                ("Fetching", TexBundle::Online),
+                ("Verifying", TexBundle::Cached),
+            ]
+        }
+        TexBundle::Local(_) => vec![("Verifying", tex_bundle.clone())],
+    };
+
+    if fs::create_dir_all(work_dir_path).is_err() {
+        return Err(String::from("Creation of the temporary directory failed!"));
+    }
+
+    let mut res = Ok(Some(()));
+    'passes: for (pass, pass_bundle) in passes.iter() {
+        for (name, latex) in documents.iter() {
+            let progress = |step: &str| {
+                let _ = tx.send(ExportJobMessage::Progress(format!("{pass} {name}: {step}")));
+                !cancel.load(Ordering::Relaxed)
+            };
+            match run_latex(name, work_dir_path, latex, pass_bundle, &progress) {
+                Ok(Some(_)) => (),
+                Ok(None) => {
+                    res = Ok(None);
+                    break 'passes;
+                }
+                Err(msg) => {
+                    res = Err(format!("{name}: {msg}"));
+                    break 'passes;
+                }
+            }
+        }
+    }
+
+    let _ = fs::remove_dir_all(work_dir_path);
+    res
+}
+
+// renders the document to output_dir_path/name.pdf, progress reports the current step and returns false
+// if the job was cancelled
+fn run_latex(
+    name: &str,
+    output_dir_path: &Path,
+    latex: &str,
+    tex_bundle: &TexBundle,
+    progress: &dyn Fn(&str) -> bool,
+) -> Result<Option<PathBuf>, String> {
+    if !progress("Loading the TeX bundle") {
+        return Ok(None);
+    }
+    if let TexBundle::Local(bundle_path) = tex_bundle {
+        if !bundle_path.exists() {
+            return Err(format!(
+                "The TeX bundle {} does not exist, check the settings!",
+                bundle_path.display()
+            ));
+        }
+    }
+
+    let mut status = NoopStatusBackend::default();
+    let config = PersistentConfig::open(false)
+        .map_err(|err| format!("Opening the TeX configuration failed: {err}"))?;
+    let bundle = match tex_bundle {
+        TexBundle::Online => config.default_bundle(false, &mut status),
+        TexBundle::Cached => config.default_bundle(true, &mut status),
+        TexBundle::Local(bundle_path) => {
+            config.make_local_file_provider(bundle_path.clone(), &mut status)
+        }
+    }
+    .map_err(|err| {
+        format!(
+            "Loading the TeX bundle failed. {} ({err})",
+            missing_resources_hint(tex_bundle)
+        )
+    })?;
+    let format_cache_path = config
+        .format_cache_path()
+        .map_err(|err| format!("Setting up the format cache failed: {err}"))?;
+
+    if !progress("Running the LaTeX engine") {
+        return Ok(None);
+    }
+    let mut sb = ProcessingSessionBuilder::default();
+    sb.bundle(bundle)
+        .primary_input_buffer(latex.as_bytes())
+        .tex_input_name(format!("{}.tex", name).as_str())
+        .format_name("latex")
+        .format_cache_path(format_cache_path)
+        .keep_logs(false)
+        .keep_intermediates(false)
+        .print_stdout(false)
+        .output_format(OutputFormat::Pdf)
+        .output_dir(output_dir_path);
+
+    let mut sess = sb
+        .create(&mut status)
+        .map_err(|err| format!("Initializing the LaTeX session failed: {err}"))?;
+    sess.run(&mut status).map_err(|err| {
+        format!(
+            "The LaTeX engine failed. {} ({err})",
+            missing_resources_hint(tex_bundle)
+        )
+    })?;
+
+    Ok(Some(output_dir_path.join(format!("{name}.pdf"))))
+}
+
+// a failing engine mostly means missing packages or fonts, the hint tells how to get them
+fn missing_resources_hint(tex_bundle: &TexBundle) -> &'static str {
+    match tex_bundle {
+        TexBundle::Online => "Without internet connection, prepare the offline exports in the settings beforehand.",
+        TexBundle::Cached => "A resource may be missing, prepare the offline exports in the settings while connected to the internet.",
+        TexBundle::Local(_) => "A resource may be missing in the local TeX bundle, check the bundle configured in the settings.",
+    }
+}
+
+// opens the file with the default application of the operating system
+pub fn open_file(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let res = Command::new("cmd")
+        .arg("/C")
+        .arg("start")
+        .arg("")
+        .arg(path)
+        .spawn();
+    #[cfg(target_os = "macos")]
+    let res = Command::new("open").arg(path).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let res = Command::new("xdg-open").arg(path).spawn();
+
+    match res {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Opening {} failed!", path.display())),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_export_jobs() {
+    let mut export_jobs = ExportJobs::new();
+    let mut senders = vec![];
+    for name in ["result", "startlist", "season"] {
+        let (tx, rx) = mpsc::channel();
+        senders.push(tx);
+        export_jobs.jobs.push(ExportJob {
+            name: String::from(name),
+            status: ExportJobStatus::Running(String::from("Starting")),
+            receiver: rx,
+            cancel: Arc::new(AtomicBool::new(false)),
+        });
+    }
+
+    senders[0]
+        .send(ExportJobMessage::Progress(String::from(
+            "Running the LaTeX engine",
+        )))
+        .unwrap();
+    senders[0]
+        .send(ExportJobMessage::Finished(Some(PathBuf::from(
+            "result.pdf",
+        ))))
+        .unwrap();
+    senders[1]
+        .send(ExportJobMessage::Failed(String::from(
+            "Loading the TeX bundle failed",
+        )))
+        .unwrap();
+    export_jobs.check_messages();
+    assert!(
+        matches!(&export_jobs.jobs[0].status, ExportJobStatus::Finished(Some(path)) if path == Path::new("result.pdf"))
+    );
+    assert!(matches!(
+        export_jobs.jobs[1].status,
+        ExportJobStatus::Failed(_)
+    ));
+    assert!(export_jobs.jobs[2].is_running());
+
+    // a cancelled job ignores later messages, its thread checks the flag
+    export_jobs.jobs[2].cancel();
+    assert!(export_jobs.jobs[2].cancel.load(Ordering::Relaxed));
+    senders[2]
+        .send(ExportJobMessage::Finished(Some(PathBuf::from(
+            "season.pdf",
+        ))))
+        .unwrap();
+    export_jobs.check_messages();
+    assert!(matches!(
+        export_jobs.jobs[2].status,
+        ExportJobStatus::Cancelled
+    ));
+
+    // a thread ending without a result, e.g. by a panic, fails its job instead of the GUI
+    let (tx, rx) = mpsc::channel::<ExportJobMessage>();
+    export_jobs.jobs.push(ExportJob {
+        name: String::from("lane_matchplans"),
+        status: ExportJobStatus::Running(String::from("Starting")),
+        receiver: rx,
+        cancel: Arc::new(AtomicBool::new(false)),
+    });
+    drop(tx);
+    export_jobs.check_messages();
+    assert!(matches!(
+        export_jobs.jobs[3].status,
+        ExportJobStatus::Failed(_)
+    ));
+
+    export_jobs.clear_finished();
+    assert!(export_jobs.jobs.is_empty());
+
+    // a missing local bundle fails with a clear message before the LaTeX engine is started
+    let (tx, _rx) = mpsc::channel();
+    let cancel = AtomicBool::new(false);
+    let progress = |step: &str| {
+        let _ = tx.send(ExportJobMessage::Progress(String::from(step)));
+        !cancel.load(Ordering::Relaxed)
+    };
+    let missing_bundle = TexBundle::Local(PathBuf::from("/nonexistent/israt-bundle.zip"));
+    let export_res = run_latex("result", Path::new("."), "", &missing_bundle, &progress);
+    assert!(export_res.unwrap_err().contains("does not exist"));
+
+    // a failed or cancelled export removes its working directory, but not the PDF of another job with the same name
+    let export_dir_path = PathBuf::from("./tmp/documents/export_jobs_test/exports");
+    let _ = fs::remove_dir_all(&export_dir_path);
+    fs::create_dir_all(&export_dir_path).unwrap();
+    fs::write(export_dir_path.join("result.pdf"), "%PDF").unwrap();
+    let work_dir_path = export_dir_path.join(".export-0");
+    let export_res = export_pdf(
+        "result",
+        &export_dir_path,
+        &work_dir_path,
+        "",
+        &missing_bundle,
+        &tx,
+        &cancel,
+    );
+    assert!(export_res.is_err());
+    assert!(!work_dir_path.exists());
+    assert!(export_dir_path.join("result.pdf").exists());
+}
+
+#[cfg(test)]
+#[test]
+fn test_lane_card_pdf() {
+    // the templates are only checked as text elsewhere, the barcode and QR code packages need the real LaTeX engine
+    let data = super::two_team_competition();
+    let latex = data
+        .get_lane_match_plans_as_latex(Some("http://localhost:8080/entry"))
+        .unwrap();
+    let output_dir_path = PathBuf::from("./tmp/documents/lane_card_test");
+    let _ = fs::remove_dir_all(&output_dir_path);
+    fs::create_dir_all(&output_dir_path).unwrap();
+
+    let pdf_path = run_latex(
+        "lane_matchplans",
+        &output_dir_path,
+        &latex,
+        &TexBundle::Online,
+        &|_| true,
+    )
+    .unwrap()
+    .unwrap();
+    assert!(fs::read(pdf_path).unwrap().starts_with(b"%PDF"));
+}
@@ -0,0 +1,247 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use super::{CompetitionData, MatchResult};
+
+// Results exchange with the federation, the upload for the rankings of the Landesverband.
+// The schema is documented in the README, it must only be changed together with SCHEMA_VERSION.
+//
+// {
+//   "schema": "israt-results",
+//   "schema_version": 1,
+//   "competition": { "name", "date" (YYYY-MM-DD), "place", "organizer", "executor", "referee",
+//                    "competition_manager", "clerk" },
+//   "groups": [{
+//     "name",
+//     "placements": [{ "place", "team" (index), "name", "region", "license_number",
+//                      "match_points": [won, lost], "stock_points": [won, lost], "quotient" }],
+//     "matches": [{ "batch", "lane", "team_a", "team_b", "points": [a, b], "result" }]
+//   }]
+// }
+//
+// Teams in the matches are referenced by their index in the group, batches and lanes start at 1.
+// The result of a match is one of "winner_a", "draw" and "winner_b", breaks are not included.
+
+pub const SCHEMA_NAME: &str = "israt-results";
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct FederationResults {
+    pub schema: &'static str,
+    pub schema_version: u32,
+    pub competition: FederationCompetition,
+    pub groups: Vec<FederationGroup>,
+}
+
+#[derive(Serialize)]
+pub struct FederationCompetition {
+    pub name: String,
+    pub date: String, // ISO 8601, e.g. "2023-05-20"
+    pub place: String,
+    pub organizer: String,
+    pub executor: String,
+    pub referee: String,
+    pub competition_manager: String,
+    pub clerk: String,
+}
+
+#[derive(Serialize)]
+pub struct FederationGroup {
+    pub name: String,
+    pub placements: Vec<FederationPlacement>, // ordered by place
+    pub matches: Vec<FederationMatch>,        // ordered by batch and lane
+}
+
+#[derive(Serialize)]
+pub struct FederationPlacement {
+    pub place: usize,
+    pub team: usize,
+    pub name: String,
+    pub region: String,
+    pub license_number: String,
+    pub match_points: [i32; 2],
+    pub stock_points: [i32; 2],
+    pub quotient: f32,
+}
+
+#[derive(Serialize)]
+pub struct FederationMatch {
+    pub batch: u32,
+    pub lane: u32,
+    pub team_a: usize,
+    pub team_b: usize,
+    pub points: [i32; 2],
+    pub result: &'static str,
+}
+
+impl FederationResults {
+    // checks everything the federation rejects on upload, returns the first problem found
+    pub fn validate(&self) -> Result<(), String> {
+        if self.competition.name.trim().is_empty() {
+            return Err(String::from("The competition has no name!"));
+        }
+        if NaiveDate::parse_from_str(&self.competition.date, "%Y-%m-%d").is_err() {
+            return Err(String::from("The date of the competition is invalid!"));
+        }
+        if self.groups.is_empty() {
+            return Err(String::from("The competition has no groups!"));
+        }
+
+        let mut license_numbers = HashSet::new();
+        for group in &self.groups {
+            for placement in &group.placements {
+                if placement.license_number.trim().is_empty() {
+                    return Err(format!(
+                        "The team \"{}\" has no license number!",
+                        placement.name
+                    ));
+                }
+                if !license_numbers.insert(placement.license_number.trim()) {
+                    return Err(format!(
+                        "The license number {} is used by more than one team!",
+                        placement.license_number
+                    ));
+                }
+            }
+
+            let count_teams = group.placements.len();
+            for _match in &group.matches {
+                if _match.team_a >= count_teams || _match.team_b >= count_teams {
+                    return Err(format!("A match in {} has an unknown team!", group.name));
+                }
+                let expected_result = match _match.points[0].cmp(&_match.points[1]) {
+                    std::cmp::Ordering::Less => "winner_b",
+                    std::cmp::Ordering::Equal => "draw",
+                    std::cmp::Ordering::Greater => "winner_a",
+                };
+                if _match.result != expected_result {
+                    return Err(format!(
+                        "The result of the match {} on lane {} in {} does not match the points!",
+                        _match.batch, _match.lane, group.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CompetitionData {
+    // the final results, only available if every match of the competition has been played
+    pub fn get_federation_results(&self) -> Result<FederationResults, String> {
+        let date = NaiveDate::parse_from_str(&self.date_string, "%d.%m.%Y")
+            .map_err(|_| String::from("The date of the competition is invalid!"))?;
+
+        let mut groups = vec![];
+        for (group_idx, teams) in self.teams.as_ref().unwrap().iter().enumerate() {
+            if !self.is_group_finished(group_idx) {
+                return Err(format!(
+                    "{} is not finished yet, the federation only accepts final results!",
+                    self.group_name(group_idx)
+                ));
+            }
+
+            let placements = self
+                .calc_interim_result_for_group(group_idx)
+                .iter()
+                .enumerate()
+                .map(|(place_idx, entry)| {
+                    let team = &teams[entry.team_idx];
+                    FederationPlacement {
+                        place: place_idx + 1,
+                        team: entry.team_idx,
+                        name: team.name.clone(),
+                        region: team.region.clone(),
+                        license_number: team.license_number.clone(),
+                        match_points: entry.match_points,
+                        stock_points: entry.stock_points,
+                        quotient: entry.quotient,
+                    }
+                })
+                .collect();
+
+            let mut matches: Vec<_> = self.matches[group_idx]
+                .iter()
+                .filter(|_match| _match.result != MatchResult::Break)
+                .collect();
+            matches.sort_by_key(|_match| (_match.batch, _match.lane));
+
+            groups.push(FederationGroup {
+                name: self.group_name(group_idx),
+                placements,
+                matches: matches
+                    .iter()
+                    .map(|_match| FederationMatch {
+                        batch: _match.batch + 1,
+                        lane: _match.lane + 1,
+                        team_a: _match.team_a,
+                        team_b: _match.team_b,
+                        points: _match.points.unwrap_or_default(),
+                        result: match _match.result {
+                            MatchResult::WinnerA => "winner_a",
+                            MatchResult::Draw => "draw",
+                            _ => "winner_b",
+                        },
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(FederationResults {
+            schema: SCHEMA_NAME,
+            schema_version: SCHEMA_VERSION,
+            competition: FederationCompetition {
+                name: self.name.clone(),
+                date: date.format("%Y-%m-%d").to_string(),
+                place: self.place.clone(),
+                organizer: self.organizer.clone(),
+                executor: self.executor.clone(),
+                referee: self.referee.clone(),
+                competition_manager: self.competition_manager.clone(),
+                clerk: self.clerk.clone(),
+            },
+            groups,
+        })
+    }
+
+    // the validated results as pretty printed JSON, ready for the upload
+    pub fn get_federation_results_as_json(&self) -> Result<String, String> {
+        let results = self.get_federation_results()?;
+        results.validate()?;
+        serde_json::to_string_pretty(&results)
+            .map_err(|err| format!("Serializing the results failed: {err}"))
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_federation_export() {
+    let mut data = super::two_team_competition();
+    data.name = String::from("Kreismeisterschaft");
+    data.date_string = String::from("20.05.2023");
+    data.teams.as_mut().unwrap()[0][0].license_number = String::from("BY-0815");
+    data.teams.as_mut().unwrap()[0][1].license_number = String::from("BY-4711");
+
+    // unfinished competitions are rejected
+    assert!(data.get_federation_results_as_json().is_err());
+
+    data.matches[0][0].set_points([3, 7]);
+    let json: serde_json::Value =
+        serde_json::from_str(&data.get_federation_results_as_json().unwrap()).unwrap();
+    assert_eq!(json["schema"], SCHEMA_NAME);
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    assert_eq!(json["competition"]["date"], "2023-05-20");
+    let group = &json["groups"][0];
+    assert_eq!(group["placements"][0]["name"], "TSV Beispiel");
+    assert_eq!(group["placements"][0]["license_number"], "BY-4711");
+    assert_eq!(group["placements"][0]["team"], 1);
+    assert_eq!(group["matches"][0]["points"], serde_json::json!([3, 7]));
+    assert_eq!(group["matches"][0]["result"], "winner_b");
+
+    // duplicate and missing license numbers fail the validation
+    data.teams.as_mut().unwrap()[0][1].license_number = String::from("BY-0815");
+    assert!(data.get_federation_results_as_json().is_err());
+    data.teams.as_mut().unwrap()[0][1].license_number = String::new();
+    assert!(data.get_federation_results_as_json().is_err());
+}
@@ -0,0 +1,580 @@
+use chrono::Local;
+
+use super::{CompetitionData, Match, MatchResult};
+
+// Static website of the competition, which can be uploaded to the club homepage as it is.
+// All pages share one stylesheet and link to each other relatively, so the folder works on every web server.
+
+pub const STYLESHEET: &str = r#"* { box-sizing: border-box; }
+body { margin: 0; font-family: "Helvetica Neue", Arial, sans-serif; color: #222; background: #f5f5f5; }
+header { background: #1f4e79; color: #fff; padding: 1em; }
+header h1 { margin: 0 0 0.25em 0; font-size: 1.5em; }
+header p { margin: 0; }
+nav { background: #2e75b6; padding: 0.5em 1em; }
+nav a { color: #fff; margin-right: 1em; text-decoration: none; white-space: nowrap; }
+nav a:hover { text-decoration: underline; }
+main { max-width: 1100px; margin: 0 auto; padding: 1em; }
+section { background: #fff; padding: 1em; margin-bottom: 1em; border-radius: 4px; }
+h2 { margin-top: 0; }
+.table-wrap { overflow-x: auto; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 0.4em 0.6em; border-bottom: 1px solid #ddd; text-align: left; }
+th { background: #e8eef5; }
+td.num, th.num { text-align: right; }
+td.self { background: #ccc; }
+.status { font-style: italic; color: #666; }
+form label { display: block; margin-top: 0.75em; }
+form input, form select, form button { width: 100%; font-size: 1.2em; padding: 0.4em; }
+form button { margin-top: 1em; background: #2e75b6; color: #fff; border: none; border-radius: 4px; }
+.message { padding: 0.5em; background: #e2f0d9; }
+.message.error { background: #f8d7da; }
+footer { text-align: center; color: #666; font-size: 0.8em; padding: 1em; }
+@media (max-width: 600px) {
+  body { font-size: 14px; }
+  th, td { padding: 0.3em; }
+  .wide { display: none; }
+}
+"#;
+
+// one page of the website
+pub struct HtmlPage {
+    pub file_name: String,
+    pub content: String,
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// the pages of the server have no navigation, the live pages subscribe to the change events of the server instead
+pub fn get_live_page(title: &str, subtitle: &str, body: &str, reload_on_update: bool) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>{title}</title>
+  <link rel="stylesheet" href="style.css">
+</head>
+<body>
+<header>
+  <h1>{title}</h1>
+  {subtitle}
+</header>
+<main>
+{body}
+</main>
+<footer>Stand: {time} – Live-Ergebnisse von ISRAT</footer>
+{script}
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        time = Local::now().format("%d.%m.%Y %H:%M:%S"),
+        script = if reload_on_update {
+            r#"<script>
+  new EventSource("events").addEventListener("update", () => location.reload());
+</script>"#
+        } else {
+            ""
+        },
+    )
+}
+
+fn group_file_name(group_idx: usize) -> String {
+    format!("group-{}.html", group_idx + 1)
+}
+
+impl CompetitionData {
+    // all pages of the website including the stylesheet
+    pub fn get_result_as_html(&self) -> Vec<HtmlPage> {
+        debug_assert!(self.teams.is_some());
+
+        let mut pages = vec![
+            HtmlPage {
+                file_name: String::from("style.css"),
+                content: String::from(STYLESHEET),
+            },
+            HtmlPage {
+                file_name: String::from("index.html"),
+                content: self.get_html_page("Übersicht", &self.get_index_html()),
+            },
+        ];
+
+        for group_idx in 0..self.teams.as_ref().unwrap().len() {
+            let group_name = self.group_name(group_idx);
+            pages.push(HtmlPage {
+                file_name: group_file_name(group_idx),
+                content: self.get_html_page(
+                    &group_name,
+                    &format!(
+                        "{}{}",
+                        self.get_standings_html(group_idx),
+                        self.get_cross_table_html(group_idx)
+                    ),
+                ),
+            });
+        }
+
+        pages.push(HtmlPage {
+            file_name: String::from("schedule.html"),
+            content: self.get_html_page("Spielplan", &self.get_schedule_html()),
+        });
+        pages.push(HtmlPage {
+            file_name: String::from("matchplans.html"),
+            content: self.get_html_page("Mannschaftsspielpläne", &self.get_team_match_plans_html()),
+        });
+        pages
+    }
+
+    // the single page served by the live results server, it reloads itself after each change
+    pub fn get_live_html(&self) -> String {
+        let groups = (0..self.teams.as_ref().unwrap().len())
+            .map(|group_idx| {
+                format!(
+                    "<h2>{}</h2>\n{}{}",
+                    escape_html(&self.group_name(group_idx)),
+                    self.get_standings_html(group_idx),
+                    self.get_upcoming_matches_html(group_idx)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("");
+
+        get_live_page(
+            &self.name,
+            &format!(
+                "<p>{}, {}</p>",
+                escape_html(&self.place),
+                escape_html(&self.date_string)
+            ),
+            &format!("{groups}{}", self.get_latest_results_html()),
+            true,
+        )
+    }
+
+    pub fn get_no_competition_html() -> String {
+        get_live_page(
+            "ISRAT",
+            "",
+            "<section><p>Derzeit wird kein Wettbewerb ausgetragen.</p></section>",
+            true,
+        )
+    }
+
+    // the matches of the batch which is played next
+    fn get_upcoming_matches_html(&self, group_idx: usize) -> String {
+        let current_batch = self.current_batch[group_idx];
+        let mut matches: Vec<&Match> = self.matches[group_idx]
+            .iter()
+            .filter(|_match| _match.batch == current_batch)
+            .collect();
+        if matches.is_empty() {
+            return String::new();
+        }
+        matches.sort_by_key(|_match| _match.lane);
+        let rows = matches
+            .iter()
+            .map(|_match| self.get_match_row_html(group_idx, _match))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            r#"<section>
+<h2>Nächster Durchgang</h2>
+<div class="table-wrap"><table>
+<tr><th class="num">Durchgang</th><th class="num">Bahn</th><th>Mannschaft A</th><th>Mannschaft B</th><th class="num">Ergebnis</th></tr>
+{rows}
+</table></div>
+</section>
+"#
+        )
+    }
+
+    // the played matches of all groups, the latest batch first
+    fn get_latest_results_html(&self) -> String {
+        let mut matches: Vec<(usize, &Match)> = self
+            .matches
+            .iter()
+            .enumerate()
+            .flat_map(|(group_idx, matches)| matches.iter().map(move |_match| (group_idx, _match)))
+            .filter(|(_, _match)| _match.points.is_some())
+            .collect();
+        if matches.is_empty() {
+            return String::new();
+        }
+        matches.sort_by_key(|(group_idx, _match)| {
+            (std::cmp::Reverse(_match.batch), *group_idx, _match.lane)
+        });
+        let rows = matches
+            .iter()
+            .map(|(group_idx, _match)| {
+                format!(
+                    "<tr><td>{}</td>{}</tr>",
+                    escape_html(&self.group_name(*group_idx)),
+                    self.get_match_cells_html(*group_idx, _match)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            r#"<section>
+<h2>Ergebnisse</h2>
+<div class="table-wrap"><table>
+<tr><th>Gruppe</th><th class="num">Durchgang</th><th class="num">Bahn</th><th>Mannschaft A</th><th>Mannschaft B</th><th class="num">Ergebnis</th></tr>
+{rows}
+</table></div>
+</section>
+"#
+        )
+    }
+
+    // the frame of every page with header, navigation and footer
+    fn get_html_page(&self, title: &str, body: &str) -> String {
+        let group_links = (0..self.teams.as_ref().unwrap().len())
+            .map(|group_idx| {
+                format!(
+                    r#"<a href="{}">{}</a>"#,
+                    group_file_name(group_idx),
+                    escape_html(&self.group_name(group_idx))
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n    ");
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>{title} – {name}</title>
+  <link rel="stylesheet" href="style.css">
+</head>
+<body>
+<header>
+  <h1>{name}</h1>
+  <p>{place}, {date}</p>
+</header>
+<nav>
+  <a href="index.html">Übersicht</a>
+  {group_links}
+  <a href="schedule.html">Spielplan</a>
+  <a href="matchplans.html">Mannschaftsspielpläne</a>
+</nav>
+<main>
+<h2>{title}</h2>
+{body}
+</main>
+<footer>Stand: {time} – erstellt mit ISRAT</footer>
+</body>
+</html>
+"#,
+            title = escape_html(title),
+            name = escape_html(&self.name),
+            place = escape_html(&self.place),
+            date = escape_html(&self.date_string),
+            time = Local::now().format("%d.%m.%Y %H:%M"),
+        )
+    }
+
+    fn get_index_html(&self) -> String {
+        let officials = [
+            ("Veranstalter", &self.organizer),
+            ("Durchführer", &self.executor),
+            ("Schiedsrichter", &self.referee),
+            ("Wettbewerbsleiter", &self.competition_manager),
+            ("Schriftführer", &self.clerk),
+        ]
+        .iter()
+        .filter(|(_, name)| !name.is_empty())
+        .map(|(role, name)| format!("<tr><th>{role}</th><td>{}</td></tr>", escape_html(name)))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+        let groups = (0..self.teams.as_ref().unwrap().len())
+            .map(|group_idx| {
+                let leader = self
+                    .calc_interim_result_for_group(group_idx)
+                    .first()
+                    .map(|entry| self.team_name(group_idx, entry.team_idx))
+                    .unwrap_or_default();
+                format!(
+                    r#"<tr><td><a href="{}">{}</a></td><td>{}</td><td class="status">{}</td></tr>"#,
+                    group_file_name(group_idx),
+                    escape_html(&self.group_name(group_idx)),
+                    escape_html(&leader),
+                    self.get_status_text(group_idx)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            r#"<section>
+<div class="table-wrap"><table>
+{officials}
+</table></div>
+{additional_text}
+</section>
+<section>
+<h2>Gruppen</h2>
+<div class="table-wrap"><table>
+<tr><th>Gruppe</th><th>Platz 1</th><th>Stand</th></tr>
+{groups}
+</table></div>
+</section>
+"#,
+            additional_text = if self.additional_text.is_empty() {
+                String::new()
+            } else {
+                format!("<p>{}</p>", escape_html(&self.additional_text))
+            }
+        )
+    }
+
+    fn get_status_text(&self, group_idx: usize) -> &'static str {
+        if self.is_group_finished(group_idx) {
+            "Endstand"
+        } else {
+            "Zwischenstand"
+        }
+    }
+
+    fn get_standings_html(&self, group_idx: usize) -> String {
+        let rows = self
+            .calc_interim_result_for_group(group_idx)
+            .iter()
+            .enumerate()
+            .map(|(place_idx, entry)| {
+                let team = &self.teams.as_ref().unwrap()[group_idx][entry.team_idx];
+                format!(
+                    r#"<tr><td class="num">{}.</td><td>{}</td><td class="wide">{}</td><td class="num">{}&nbsp;:&nbsp;{}</td><td class="num">{:.3}</td><td class="num wide">{}&nbsp;:&nbsp;{}</td></tr>"#,
+                    place_idx + 1,
+                    escape_html(&team.name),
+                    escape_html(&team.region),
+                    entry.match_points[0],
+                    entry.match_points[1],
+                    entry.quotient,
+                    entry.stock_points[0],
+                    entry.stock_points[1],
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            r#"<section>
+<h2>Tabelle</h2>
+<p class="status">{}</p>
+<div class="table-wrap"><table>
+<tr><th class="num">Rang</th><th>Mannschaft</th><th class="wide">Kreis</th><th class="num">Spielpunkte</th><th class="num">Quotient</th><th class="num wide">Stockpunkte</th></tr>
+{rows}
+</table></div>
+</section>
+"#,
+            self.get_status_text(group_idx)
+        )
+    }
+
+    // every team against every other team, the points are shown from the view of the row team
+    fn get_cross_table_html(&self, group_idx: usize) -> String {
+        let teams = &self.teams.as_ref().unwrap()[group_idx];
+
+        let header = (0..teams.len())
+            .map(|team_idx| format!(r#"<th class="num">{}</th>"#, team_idx + 1))
+            .collect::<Vec<String>>()
+            .join("");
+
+        let rows = teams
+            .iter()
+            .enumerate()
+            .map(|(row_idx, team)| {
+                let cells = (0..teams.len())
+                    .map(|col_idx| {
+                        if row_idx == col_idx {
+                            return String::from(r#"<td class="self"></td>"#);
+                        }
+                        let results = self.matches[group_idx]
+                            .iter()
+                            .filter(|_match| _match.result != MatchResult::Break)
+                            .filter_map(|_match| match _match.points {
+                                Some(points)
+                                    if _match.team_a == row_idx && _match.team_b == col_idx =>
+                                {
+                                    Some(format!("{}:{}", points[0], points[1]))
+                                }
+                                Some(points)
+                                    if _match.team_b == row_idx && _match.team_a == col_idx =>
+                                {
+                                    Some(format!("{}:{}", points[1], points[0]))
+                                }
+                                _ => None,
+                            })
+                            .collect::<Vec<String>>()
+                            .join("<br>");
+                        format!(r#"<td class="num">{results}</td>"#)
+                    })
+                    .collect::<Vec<String>>()
+                    .join("");
+                format!(
+                    "<tr><td>{}. {}</td>{cells}</tr>",
+                    row_idx + 1,
+                    escape_html(&team.name)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        format!(
+            r#"<section>
+<h2>Kreuztabelle</h2>
+<div class="table-wrap"><table>
+<tr><th>Mannschaft</th>{header}</tr>
+{rows}
+</table></div>
+</section>
+"#
+        )
+    }
+
+    fn get_match_row_html(&self, group_idx: usize, _match: &Match) -> String {
+        format!("<tr>{}</tr>", self.get_match_cells_html(group_idx, _match))
+    }
+
+    fn get_match_cells_html(&self, group_idx: usize, _match: &Match) -> String {
+        if _match.result == MatchResult::Break {
+            return format!(
+                r#"<td class="num">{}</td><td></td><td>{}</td><td>Pause</td><td></td>"#,
+                _match.batch + 1,
+                escape_html(&self.team_name(group_idx, _match.team_a))
+            );
+        }
+        format!(
+            r#"<td class="num">{}</td><td class="num">{}</td><td>{}</td><td>{}</td><td class="num">{}</td>"#,
+            _match.batch + 1,
+            _match.lane + 1,
+            escape_html(&self.team_name(group_idx, _match.team_a)),
+            escape_html(&self.team_name(group_idx, _match.team_b)),
+            _match.points.map_or(String::new(), |points| format!(
+                "{}&nbsp;:&nbsp;{}",
+                points[0], points[1]
+            ))
+        )
+    }
+
+    fn get_schedule_html(&self) -> String {
+        self.matches
+            .iter()
+            .enumerate()
+            .map(|(group_idx, matches)| {
+                let mut matches: Vec<&Match> = matches.iter().collect();
+                matches.sort_by_key(|_match| (_match.batch, _match.lane));
+                let rows = matches
+                    .iter()
+                    .map(|_match| self.get_match_row_html(group_idx, _match))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+
+                format!(
+                    r#"<section>
+<h2>{}</h2>
+<div class="table-wrap"><table>
+<tr><th class="num">Durchgang</th><th class="num">Bahn</th><th>Mannschaft A</th><th>Mannschaft B</th><th class="num">Ergebnis</th></tr>
+{rows}
+</table></div>
+</section>
+"#,
+                    escape_html(&self.group_name(group_idx))
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("")
+    }
+
+    // the matches of every team in the order they are played
+    fn get_team_match_plans_html(&self) -> String {
+        self.teams
+            .as_ref()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .flat_map(|(group_idx, teams)| {
+                teams.iter().enumerate().map(move |(team_idx, team)| {
+                    let mut matches: Vec<&Match> = self.matches[group_idx]
+                        .iter()
+                        .filter(|_match| _match.team_a == team_idx || _match.team_b == team_idx)
+                        .collect();
+                    matches.sort_by_key(|_match| _match.batch);
+                    let rows = matches
+                        .iter()
+                        .map(|_match| self.get_match_row_html(group_idx, _match))
+                        .collect::<Vec<String>>()
+                        .join("\n");
+
+                    format!(
+                        r#"<section>
+<h2>{} – {}. {}</h2>
+<div class="table-wrap"><table>
+<tr><th class="num">Durchgang</th><th class="num">Bahn</th><th>Mannschaft A</th><th>Mannschaft B</th><th class="num">Ergebnis</th></tr>
+{rows}
+</table></div>
+</section>
+"#,
+                        escape_html(&self.group_name(group_idx)),
+                        team_idx + 1,
+                        escape_html(&team.name)
+                    )
+                })
+            })
+            .collect::<Vec<String>>()
+            .join("")
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_html_export() {
+    assert_eq!(
+        escape_html(r#"<b>"Tom & Jerry's"</b>"#),
+        "&lt;b&gt;&quot;Tom &amp; Jerry&#39;s&quot;&lt;/b&gt;"
+    );
+
+    let mut data = super::two_team_competition();
+    data.teams.as_mut().unwrap()[0][0].name = String::from("SV <Musterstadt>");
+    data.matches[0][0].set_points([7, 3]);
+    data.current_batch = vec![1];
+
+    let pages = data.get_result_as_html();
+    let file_names: Vec<&str> = pages.iter().map(|page| page.file_name.as_str()).collect();
+    assert_eq!(
+        file_names,
+        vec![
+            "style.css",
+            "index.html",
+            "group-1.html",
+            "schedule.html",
+            "matchplans.html"
+        ]
+    );
+
+    // the cross table shows the result from the view of both teams
+    let group_page = &pages[2].content;
+    assert!(group_page.contains(r#"<td class="num">7:3</td>"#));
+    assert!(group_page.contains(r#"<td class="num">3:7</td>"#));
+    assert!(group_page.contains("SV &lt;Musterstadt&gt;"));
+    assert!(!group_page.contains("<Musterstadt>"));
+}
@@ -0,0 +1,191 @@
+use chrono::{NaiveDateTime, Utc};
+
+use super::{CompetitionData, Match, MatchResult};
+
+// iCalendar files of the planned matches, so that the teams can import their matches into their calendars.
+// The times are written as local times without a time zone, like the times printed on the match plans.
+
+const MAX_LINE_LEN: usize = 75; // lines longer than this have to be folded, see RFC 5545
+
+// escapes the special characters of iCalendar text values
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// writes a content line, long lines are folded at character boundaries
+fn push_line(ics: &mut String, line: &str) {
+    let mut line_len = 0;
+    for c in line.chars() {
+        if line_len + c.len_utf8() > MAX_LINE_LEN {
+            ics.push_str("\r\n ");
+            line_len = 1;
+        }
+        ics.push(c);
+        line_len += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+fn format_time(time: &NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+// a file name without characters which are not allowed on some systems
+pub fn calendar_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl CompetitionData {
+    // the calendar of a single team with its matches and breaks
+    pub fn get_team_calendar_as_ics(
+        &self,
+        group_idx: usize,
+        team_idx: usize,
+    ) -> Result<String, String> {
+        let mut matches: Vec<&Match> = self.matches[group_idx]
+            .iter()
+            .filter(|_match| _match.team_a == team_idx || _match.team_b == team_idx)
+            .collect();
+        matches.sort_by_key(|_match| _match.batch);
+        self.get_calendar_as_ics(
+            &format!(
+                "{} – {}",
+                self.name,
+                self.teams.as_ref().unwrap()[group_idx][team_idx].name
+            ),
+            group_idx,
+            &matches,
+            Some(team_idx),
+        )
+    }
+
+    // the calendar of a group with all its matches
+    pub fn get_group_calendar_as_ics(&self, group_idx: usize) -> Result<String, String> {
+        let mut matches: Vec<&Match> = self.matches[group_idx]
+            .iter()
+            .filter(|_match| _match.result != MatchResult::Break)
+            .collect();
+        matches.sort_by_key(|_match| (_match.batch, _match.lane));
+        self.get_calendar_as_ics(
+            &format!("{} – {}", self.name, self.group_name(group_idx)),
+            group_idx,
+            &matches,
+            None,
+        )
+    }
+
+    fn get_calendar_as_ics(
+        &self,
+        calendar_name: &str,
+        group_idx: usize,
+        matches: &[&Match],
+        team_idx: Option<usize>,
+    ) -> Result<String, String> {
+        if self.batch_start_time(0).is_none() {
+            return Err(String::from(
+                "Enter the date and the start time of the competition to export calendars.",
+            ));
+        }
+
+        let time_stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut ics = String::new();
+        push_line(&mut ics, "BEGIN:VCALENDAR");
+        push_line(&mut ics, "VERSION:2.0");
+        push_line(&mut ics, "PRODID:-//ISRAT//Match plans//DE");
+        push_line(&mut ics, "CALSCALE:GREGORIAN");
+        push_line(
+            &mut ics,
+            &format!("X-WR-CALNAME:{}", escape_text(calendar_name)),
+        );
+
+        for _match in matches {
+            let start = self.batch_start_time(_match.batch).unwrap();
+            let end = self.batch_start_time(_match.batch + 1).unwrap();
+            let team_a = self.team_name(group_idx, _match.team_a);
+
+            let (summary, location) = if _match.result == MatchResult::Break {
+                (format!("Pause {team_a}"), self.place.clone())
+            } else {
+                let team_b = self.team_name(group_idx, _match.team_b);
+                let summary = match team_idx {
+                    Some(team_idx) if team_idx == _match.team_b => format!("{team_b} – {team_a}"),
+                    _ => format!("{team_a} – {team_b}"),
+                };
+                (summary, format!("{}, Bahn {}", self.place, _match.lane + 1))
+            };
+
+            push_line(&mut ics, "BEGIN:VEVENT");
+            // the same match has the same id in all calendars, so that it is not imported twice
+            push_line(
+                &mut ics,
+                &format!(
+                    "UID:{}-{}-g{}-b{}-t{}-{}@israt",
+                    calendar_file_name(&self.name),
+                    calendar_file_name(&self.date_string),
+                    group_idx + 1,
+                    _match.batch + 1,
+                    _match.team_a + 1,
+                    _match.team_b + 1
+                ),
+            );
+            push_line(&mut ics, &format!("DTSTAMP:{time_stamp}"));
+            push_line(&mut ics, &format!("DTSTART:{}", format_time(&start)));
+            push_line(&mut ics, &format!("DTEND:{}", format_time(&end)));
+            push_line(&mut ics, &format!("SUMMARY:{}", escape_text(&summary)));
+            push_line(&mut ics, &format!("LOCATION:{}", escape_text(&location)));
+            push_line(
+                &mut ics,
+                &format!(
+                    "DESCRIPTION:{}",
+                    escape_text(&format!(
+                        "{}, {}. Durchgang",
+                        self.group_name(group_idx),
+                        _match.batch + 1
+                    ))
+                ),
+            );
+            push_line(&mut ics, "END:VEVENT");
+        }
+
+        push_line(&mut ics, "END:VCALENDAR");
+        Ok(ics)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_ics_export() {
+    let mut folded = String::new();
+    push_line(&mut folded, &"ä".repeat(50));
+    assert!(folded.split("\r\n").all(|line| line.len() <= MAX_LINE_LEN));
+    assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+
+    let mut data = super::two_team_competition();
+    data.name = String::from("Mustermeisterschaft");
+    data.date_string = String::from("01.01.2022");
+    data.place = String::from("Musterstadt");
+    data.batch_duration_minutes = 20;
+    data.matches[0][0].batch = 1;
+    data.matches[0][0].lane = 2;
+
+    // calendars need planned times
+    assert!(data.get_group_calendar_as_ics(0).is_err());
+
+    data.start_time = String::from("09:00");
+    let ics = data.get_team_calendar_as_ics(0, 1).unwrap();
+    assert!(ics.contains("DTSTART:20220101T092000\r\n"));
+    assert!(ics.contains("DTEND:20220101T094000\r\n"));
+    assert!(ics.contains("SUMMARY:TSV Beispiel – SV Musterstadt\r\n"));
+    assert!(ics.contains("LOCATION:Musterstadt\\, Bahn 3\r\n"));
+}
@@ -0,0 +1,343 @@
+use std::{
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{read_write::read_from_json_value, CompetitionData, RegionScoring};
+
+// The journal is an append-only file next to the competition file. Every change of the competition data is written
+// to it immediately, so that the state can be rebuilt by replaying the journal over the last snapshot.
+
+// one line of the journal
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub time: String, // RFC 3339 timestamp of the change
+    pub event: JournalEvent,
+}
+
+// The changes are stored with absolute values, so that applying an event twice has no further effect.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum JournalEvent {
+    // the complete competition data, used as start for the replay
+    Snapshot {
+        data: Value,
+    },
+    // the competition file was saved and contains all previous changes
+    Saved,
+    BatchSubmitted {
+        group_idx: usize,
+        batch: u32,
+        results: Vec<LaneResult>,
+    },
+    ResultCorrected {
+        group_idx: usize,
+        batch: u32,
+        lane: u32,
+        points: [i32; 2],
+    },
+    RegionScoringChanged {
+        region_scoring: RegionScoring,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LaneResult {
+    pub lane: u32,
+    pub points: [i32; 2],
+}
+
+impl Display for JournalEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalEvent::Snapshot { .. } => write!(f, "Snapshot of the competition"),
+            JournalEvent::Saved => write!(f, "Competition file saved"),
+            JournalEvent::BatchSubmitted {
+                group_idx, batch, ..
+            } => write!(f, "Group {}: batch {} submitted", group_idx + 1, batch + 1),
+            JournalEvent::ResultCorrected {
+                group_idx,
+                batch,
+                lane,
+                points,
+            } => write!(
+                f,
+                "Group {}: result of batch {} on lane {} corrected to {} : {}",
+                group_idx + 1,
+                batch + 1,
+                lane + 1,
+                points[0],
+                points[1]
+            ),
+            JournalEvent::RegionScoringChanged { region_scoring } => {
+                write!(f, "Region scoring changed to {region_scoring}")
+            }
+        }
+    }
+}
+
+impl JournalEvent {
+    pub fn snapshot(data: &CompetitionData) -> Result<Self, String> {
+        match serde_json::from_str(&data.get_as_json_string()?) {
+            Ok(data) => Ok(JournalEvent::Snapshot { data }),
+            Err(_) => Err(String::from("Serialization of the snapshot failed!")),
+        }
+    }
+
+    // applies the change to the competition data, snapshots and save markers do not change the data
+    pub fn apply(&self, data: &mut CompetitionData) -> Result<(), String> {
+        match self {
+            JournalEvent::Snapshot { .. } | JournalEvent::Saved => (),
+            JournalEvent::BatchSubmitted {
+                group_idx,
+                batch,
+                results,
+            } => {
+                for result in results {
+                    find_match(data, *group_idx, *batch, result.lane)?.set_points(result.points);
+                }
+                data.current_batch[*group_idx] = data.current_batch[*group_idx].max(batch + 1);
+            }
+            JournalEvent::ResultCorrected {
+                group_idx,
+                batch,
+                lane,
+                points,
+            } => find_match(data, *group_idx, *batch, *lane)?.set_points(*points),
+            JournalEvent::RegionScoringChanged { region_scoring } => {
+                data.region_scoring = *region_scoring
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find_match(
+    data: &mut CompetitionData,
+    group_idx: usize,
+    batch: u32,
+    lane: u32,
+) -> Result<&mut super::Match, String> {
+    data.matches
+        .get_mut(group_idx)
+        .and_then(|matches| {
+            matches
+                .iter_mut()
+                .find(|_match| _match.batch == batch && _match.lane == lane)
+        })
+        .ok_or(format!(
+            "The journal references an unknown match (group {}, batch {}, lane {})!",
+            group_idx + 1,
+            batch + 1,
+            lane + 1
+        ))
+}
+
+// the journal of a competition file is stored next to it
+pub fn journal_path(file_path: &Path) -> PathBuf {
+    file_path.with_extension("journal")
+}
+
+// appends the event to the journal of the competition file and waits until it is written to the disk,
+// a missing journal is started with a snapshot of the current data instead
+pub fn record_event(
+    file_path: &Path,
+    data: &CompetitionData,
+    event: JournalEvent,
+) -> Result<(), String> {
+    let journal_path = journal_path(file_path);
+    let event = if journal_path.is_file() {
+        event
+    } else {
+        JournalEvent::snapshot(data)?
+    };
+
+    let entry = JournalEntry {
+        time: Local::now().to_rfc3339(),
+        event,
+    };
+    let mut line = match serde_json::to_string(&entry) {
+        Ok(line) => line,
+        Err(_) => return Err(String::from("Serialization of the journal entry failed!")),
+    };
+    line.push('\n');
+
+    let write_res = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&journal_path)
+        .and_then(|mut file| {
+            file.write_all(line.as_bytes())
+                .and_then(|_| file.sync_data())
+        });
+    match write_res {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!(
+            "Write to journal {} failed!",
+            journal_path.display()
+        )),
+    }
+}
+
+// starts the journal with a snapshot, if the competition file has none yet
+pub fn start_journal(file_path: &Path, data: &CompetitionData) -> Result<(), String> {
+    if journal_path(file_path).is_file() {
+        return Ok(());
+    }
+    record_event(file_path, data, JournalEvent::snapshot(data)?)
+}
+
+// reads all entries of the journal, a missing journal has no entries
+pub fn read_journal(file_path: &Path) -> Result<Vec<JournalEntry>, String> {
+    let journal_path = journal_path(file_path);
+    if !journal_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = match fs::read_to_string(&journal_path) {
+        Ok(content) => content,
+        Err(_) => {
+            return Err(format!(
+                "Error whilst reading file: {}",
+                journal_path.display()
+            ))
+        }
+    };
+
+    let mut entries = vec![];
+    for (line_idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            // a crash while appending can only damage the last line, which is ignored
+            Err(_) if line_idx + 1 == content.lines().count() => {
+                eprintln!("[read_journal]: Ignoring incomplete last journal entry");
+            }
+            Err(_) => {
+                return Err(format!(
+                    "Line {} of the journal {} is damaged!",
+                    line_idx + 1,
+                    journal_path.display()
+                ))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+// Rebuilds the current state by replaying the changes after the last snapshot or save marker.
+// The saved data is the start for the replay if the last marker is a save marker.
+pub fn replay(
+    entries: &[JournalEntry],
+    saved_data: &CompetitionData,
+) -> Result<CompetitionData, String> {
+    let start_idx = entries.iter().rposition(|entry| {
+        matches!(
+            entry.event,
+            JournalEvent::Snapshot { .. } | JournalEvent::Saved
+        )
+    });
+    replay_from(entries, start_idx, Some(saved_data))
+}
+
+// Rebuilds the state after the first entry_count entries, starting at the last snapshot before.
+// The save markers are skipped, because the saved data of earlier saves is not available anymore.
+pub fn replay_history(
+    entries: &[JournalEntry],
+    entry_count: usize,
+) -> Result<CompetitionData, String> {
+    let entries = &entries[..entry_count.min(entries.len())];
+    let start_idx = entries
+        .iter()
+        .rposition(|entry| matches!(entry.event, JournalEvent::Snapshot { .. }));
+    replay_from(entries, start_idx, None)
+}
+
+fn replay_from(
+    entries: &[JournalEntry],
+    start_idx: Option<usize>,
+    saved_data: Option<&CompetitionData>,
+) -> Result<CompetitionData, String> {
+    let mut data = match start_idx.map(|idx| &entries[idx].event) {
+        Some(JournalEvent::Snapshot { data }) => read_from_json_value(data.clone())?,
+        _ => match saved_data {
+            Some(saved_data) => saved_data.clone(),
+            None => {
+                return Err(String::from(
+                    "The journal contains no snapshot to start from!",
+                ))
+            }
+        },
+    };
+
+    let replay_start = start_idx.map_or(0, |idx| idx + 1);
+    for entry in &entries[replay_start..] {
+        entry.event.apply(&mut data)?;
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+#[test]
+fn test_journal_replay() {
+    use super::{read_write::save_to_file, two_team_competition, MatchResult};
+
+    let path = PathBuf::from("./tmp/documents/journal_test/competition.json");
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+
+    let mut data = two_team_competition();
+    data.name = String::from("Journalmeisterschaft");
+    assert!(save_to_file(path.clone(), &data, 0).is_ok());
+    assert!(start_journal(&path, &data).is_ok());
+
+    // the saved file only contains the data before the submit, e.g. after a crash
+    let saved_data = data.clone();
+    let submit = JournalEvent::BatchSubmitted {
+        group_idx: 0,
+        batch: 0,
+        results: vec![LaneResult {
+            lane: 0,
+            points: [7, 3],
+        }],
+    };
+    assert!(submit.apply(&mut data).is_ok());
+    assert!(record_event(&path, &data, submit).is_ok());
+    let correction = JournalEvent::ResultCorrected {
+        group_idx: 0,
+        batch: 0,
+        lane: 0,
+        points: [3, 7],
+    };
+    assert!(correction.apply(&mut data).is_ok());
+    assert!(record_event(&path, &data, correction).is_ok());
+
+    let entries = read_journal(&path).unwrap();
+    assert_eq!(entries.len(), 3);
+
+    let replayed = replay(&entries, &saved_data).unwrap();
+    assert_eq!(replayed.current_batch, vec![1]);
+    assert_eq!(replayed.matches[0][0].points, Some([3, 7]));
+    assert_eq!(replayed.matches[0][0].result, MatchResult::WinnerB);
+
+    // the history can be replayed step by step
+    let after_submit = replay_history(&entries, 2).unwrap();
+    assert_eq!(after_submit.matches[0][0].result, MatchResult::WinnerA);
+
+    // setup changes are journaled as snapshot
+    let mut competition = super::Competition::empty();
+    competition.absolute_file_path = Some(path.clone());
+    data.teams.as_mut().unwrap()[0][1].name = String::from("TSV Beispiel 2");
+    competition.data = Some(data);
+    assert!(competition.record_journal_snapshot().is_ok());
+    let replayed = replay(&read_journal(&path).unwrap(), &saved_data).unwrap();
+    assert_eq!(replayed.teams.unwrap()[0][1].name, "TSV Beispiel 2");
+    assert_eq!(replayed.matches[0][0].points, Some([3, 7]));
+}
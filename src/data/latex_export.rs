@@ -0,0 +1,511 @@
+use chrono::Local;
+use minijinja::context;
+use serde::Serialize;
+
+use super::{
+    match_id,
+    templates::{
+        render_template, LANE_MATCH_PLANS_TEMPLATE, REGION_RESULT_TEMPLATE, RESULT_LIST_TEMPLATE,
+        START_LIST_TEMPLATE, TEAM_MATCH_PLANS_TEMPLATE,
+    },
+    CompetitionData, InterimResultEntry, Match, MatchResult, Team,
+};
+
+// The structured context passed to the LaTeX templates, see resources/templates for the built-in templates.
+// Numbers of teams, batches and lanes start at 1 like on the printed documents.
+
+const PLAYER_NAMES_UNTIL: usize = 3; // the number of teams per group whose player names are printed on the result list
+
+#[derive(Serialize)]
+struct CompetitionContext {
+    name: String,
+    date: String,
+    place: String,
+    organizer: String,
+    executor: String,
+    referee: String,
+    competition_manager: String,
+    clerk: String,
+    additional_text_lines: Vec<String>,
+    is_final: bool, // whether all matches of all groups were played
+}
+
+#[derive(Serialize)]
+struct TeamContext {
+    number: usize,
+    name: String,
+    region: String,
+    license_number: String,
+    player_names: Vec<String>, // only the entered names
+}
+
+#[derive(Serialize)]
+struct StandingContext {
+    place: usize,
+    team: TeamContext,
+    match_points: [i32; 2],
+    stock_points: [i32; 2],
+    quotient: String, // formatted with three decimals
+    show_player_names: bool,
+}
+
+#[derive(Serialize)]
+struct MatchContext {
+    id: String, // the match id printed as barcode
+    batch: u32,
+    lane: u32,
+    is_break: bool,
+    team_a: TeamContext,         // the pausing team for breaks
+    team_b: Option<TeamContext>, // None for breaks
+    points: Option<[i32; 2]>,
+}
+
+#[derive(Serialize)]
+struct GroupContext {
+    name: String,
+    is_final: bool,
+    current_batch: u32, // the number of submitted batches
+    teams: Vec<TeamContext>,
+    standings: Vec<StandingContext>,
+    matches: Vec<MatchContext>, // ordered by batch and lane, including the breaks
+    show_header: bool,          // whether the competition header is printed above the group
+    page_break_after: bool,     // whether the group ends the page
+}
+
+#[derive(Serialize)]
+struct RegionContext {
+    place: usize,
+    name: String,
+    count_teams: u32,
+    points: i32,
+    match_points: [i32; 2],
+    stock_points: [i32; 2],
+    quotient: String,
+}
+
+// escapes the characters with a special meaning in LaTeX, so that the text is printed as entered, all text entered
+// by the user is escaped with it before it is passed to the templates
+pub fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str(r"\textasciitilde{}"),
+            '^' => escaped.push_str(r"\textasciicircum{}"),
+            // brackets would be taken as optional argument after a line break, e.g. \\[Gast]
+            '[' => escaped.push_str("{[}"),
+            ']' => escaped.push_str("{]}"),
+            // line breaks are inserted by the templates only
+            '\n' | '\r' | '\t' => escaped.push(' '),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn team_context(team_idx: usize, team: &Team) -> TeamContext {
+    TeamContext {
+        number: team_idx + 1,
+        name: escape_latex(&team.name),
+        region: escape_latex(&team.region),
+        license_number: escape_latex(&team.license_number),
+        player_names: team
+            .player_names
+            .iter()
+            .filter_map(|name| name.as_deref())
+            .map(escape_latex)
+            .collect(),
+    }
+}
+
+impl CompetitionData {
+    fn competition_context(&self) -> CompetitionContext {
+        CompetitionContext {
+            name: escape_latex(&self.name),
+            date: escape_latex(&self.date_string),
+            place: escape_latex(&self.place),
+            organizer: escape_latex(&self.organizer),
+            executor: escape_latex(&self.executor),
+            referee: escape_latex(&self.referee),
+            competition_manager: escape_latex(&self.competition_manager),
+            clerk: escape_latex(&self.clerk),
+            additional_text_lines: self.additional_text.lines().map(escape_latex).collect(),
+            is_final: (0..self.matches.len()).all(|group_idx| self.is_group_finished(group_idx)),
+        }
+    }
+
+    // the standings are taken from the given interim results if available, e.g. the ones shown in the GUI
+    fn group_contexts(
+        &self,
+        interim_results: Option<&Vec<Option<Vec<InterimResultEntry>>>>,
+    ) -> Vec<GroupContext> {
+        self.teams
+            .as_ref()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(group_idx, teams)| {
+                let calculated_result;
+                let group_result = match interim_results
+                    .and_then(|interim_results| interim_results.get(group_idx))
+                    .and_then(|interim_result| interim_result.as_ref())
+                {
+                    Some(interim_result) => interim_result,
+                    None => {
+                        calculated_result = self.calc_interim_result_for_group(group_idx);
+                        &calculated_result
+                    }
+                };
+
+                let mut matches: Vec<_> = self.matches[group_idx].iter().collect();
+                matches.sort_by_key(|_match| (_match.batch, _match.lane));
+
+                GroupContext {
+                    name: escape_latex(&self.group_name(group_idx)),
+                    is_final: self.is_group_finished(group_idx),
+                    current_batch: self.current_batch[group_idx],
+                    teams: teams
+                        .iter()
+                        .enumerate()
+                        .map(|(team_idx, team)| team_context(team_idx, team))
+                        .collect(),
+                    standings: group_result
+                        .iter()
+                        .enumerate()
+                        .map(|(place_idx, entry)| StandingContext {
+                            place: place_idx + 1,
+                            team: team_context(entry.team_idx, &teams[entry.team_idx]),
+                            match_points: entry.match_points,
+                            stock_points: entry.stock_points,
+                            quotient: format!("{:.3}", entry.quotient),
+                            show_player_names: place_idx < PLAYER_NAMES_UNTIL,
+                        })
+                        .collect(),
+                    matches: matches
+                        .iter()
+                        .map(|_match| {
+                            let is_break = _match.result == MatchResult::Break;
+                            MatchContext {
+                                id: match_id(group_idx, _match.batch, _match.lane),
+                                batch: _match.batch + 1,
+                                lane: _match.lane + 1,
+                                is_break,
+                                team_a: team_context(_match.team_a, &teams[_match.team_a]),
+                                team_b: if is_break {
+                                    None
+                                } else {
+                                    Some(team_context(_match.team_b, &teams[_match.team_b]))
+                                },
+                                points: _match.points,
+                            }
+                        })
+                        .collect(),
+                    show_header: true,
+                    page_break_after: true,
+                }
+            })
+            .collect()
+    }
+
+    pub(super) fn get_result_as_latex(
+        &self,
+        current_interim_result: &Vec<Option<Vec<InterimResultEntry>>>,
+    ) -> Result<String, String> {
+        let mut groups = self.group_contexts(Some(current_interim_result));
+
+        // a page holds about 16 teams without player names, a team with player names needs three lines
+        let mut count_lines = 0;
+        let mut previous_new_page = true; // the header is printed on each new page
+        for group in groups.iter_mut() {
+            count_lines += group
+                .standings
+                .iter()
+                .map(|standing| if standing.show_player_names { 3 } else { 1 })
+                .sum::<usize>();
+            group.show_header = previous_new_page;
+            group.page_break_after = count_lines > 16;
+            previous_new_page = group.page_break_after;
+        }
+
+        render_template(
+            RESULT_LIST_TEMPLATE,
+            context! {
+                competition => self.competition_context(),
+                created_at => Local::now().format("%d.%m.%Y %H:%M").to_string(),
+                groups => groups,
+            },
+        )
+    }
+
+    pub(super) fn get_start_list_as_latex(&self) -> Result<String, String> {
+        let mut groups = self.group_contexts(None);
+
+        let mut previous_new_page = true;
+        for group in groups.iter_mut() {
+            group.show_header = previous_new_page;
+            group.page_break_after = self.team_distribution[1] > 15;
+            previous_new_page = group.page_break_after;
+        }
+
+        render_template(
+            START_LIST_TEMPLATE,
+            context! {
+                competition => self.competition_context(),
+                created_at => Local::now().format("%d.%m.%Y %H:%M").to_string(),
+                groups => groups,
+            },
+        )
+    }
+
+    pub(super) fn get_region_result_as_latex(&self) -> Result<String, String> {
+        let regions: Vec<RegionContext> = self
+            .calc_region_result()
+            .into_iter()
+            .enumerate()
+            .map(|(place_idx, entry)| RegionContext {
+                place: place_idx + 1,
+                name: escape_latex(&entry.region),
+                count_teams: entry.count_teams,
+                points: entry.points,
+                match_points: entry.match_points,
+                stock_points: entry.stock_points,
+                quotient: format!("{:.3}", entry.quotient),
+            })
+            .collect();
+
+        render_template(
+            REGION_RESULT_TEMPLATE,
+            context! {
+                competition => self.competition_context(),
+                created_at => Local::now().format("%d.%m.%Y %H:%M").to_string(),
+                region_scoring => self.region_scoring,
+                regions => regions,
+            },
+        )
+    }
+
+    pub(super) fn get_team_match_plans_as_latex(&self) -> Result<String, String> {
+        render_template(
+            TEAM_MATCH_PLANS_TEMPLATE,
+            context! {
+                competition => self.competition_context(),
+                groups => self.group_contexts(None),
+            },
+        )
+    }
+
+    // each lane card carries its match id as Code 39 barcode and QR code, the QR code links to the entry page if available
+    pub(super) fn get_lane_match_plans_as_latex(
+        &self,
+        entry_url: Option<&str>,
+    ) -> Result<String, String> {
+        render_template(
+            LANE_MATCH_PLANS_TEMPLATE,
+            context! {
+                competition => self.competition_context(),
+                groups => self.group_contexts(None),
+                entry_url => entry_url,
+            },
+        )
+    }
+
+    // a group of numbered teams whose first batch has one match and a break of team 3
+    fn sample_competition(count_teams: u32) -> CompetitionData {
+        let mut data = CompetitionData::empty();
+        data.name = String::from("ISRAT");
+        data.count_teams = count_teams;
+        data.team_distribution = [1, count_teams];
+        data.teams = Some(vec![(1..=count_teams)
+            .map(|number| Team {
+                name: format!("Team {number}"),
+                region: String::from("Kreis 1"),
+                player_names: Default::default(),
+                license_number: String::new(),
+            })
+            .collect()]);
+        data.current_batch = vec![0];
+        data.matches = vec![vec![
+            Match {
+                team_a: 0,
+                team_b: 1,
+                points: None,
+                result: MatchResult::NotPlayed,
+                batch: 0,
+                lane: 0,
+            },
+            Match {
+                team_a: 2,
+                team_b: 2,
+                points: None,
+                result: MatchResult::Break,
+                batch: 0,
+                lane: 0,
+            },
+        ]];
+        data
+    }
+
+    // the PDF documents of a small competition, rendered once to fetch all TeX resources for offline exports
+    pub(super) fn get_sample_documents_as_latex() -> Result<Vec<(String, String)>, String> {
+        let data = Self::sample_competition(3);
+
+        Ok(vec![
+            (
+                String::from("result"),
+                data.get_result_as_latex(&vec![None])?,
+            ),
+            (String::from("startlist"), data.get_start_list_as_latex()?),
+            (
+                String::from("region_result"),
+                data.get_region_result_as_latex()?,
+            ),
+            (
+                String::from("team_matchplans"),
+                data.get_team_match_plans_as_latex()?,
+            ),
+            (
+                String::from("lane_matchplans"),
+                data.get_lane_match_plans_as_latex(Some("http://localhost:8080/entry"))?,
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_latex_export() {
+    // the footer with the additional text ends a page, i.e. a group of more than 16 lines
+    let mut data = CompetitionData::sample_competition(11);
+    data.name = String::from("Kreismeisterschaft");
+    data.additional_text = String::from("Zeile 1\nZeile 2");
+    data.group_names = Some(vec![String::from("Gruppe A")]);
+    for team in data.teams.as_mut().unwrap()[0].iter_mut() {
+        team.player_names[0] = Some(String::from("Anna"));
+        team.player_names[1] = Some(String::from("Bert"));
+    }
+    data.matches[0][0].set_points([7, 3]);
+    data.matches[0].push(Match {
+        team_a: 2,
+        team_b: 0,
+        points: None,
+        result: MatchResult::NotPlayed,
+        batch: 1,
+        lane: 0,
+    });
+    data.current_batch = vec![1];
+
+    let result_list = data.get_result_as_latex(&vec![None]).unwrap();
+    assert!(result_list.contains(r"\textbf{Zwischenliste Gruppe A nach Spiel 1}"));
+    assert!(result_list.contains(r"Team 1\\ \footnotesize Anna, Bert"));
+    assert!(result_list.contains(r"Zeile 1\\"));
+
+    let start_list = data.get_start_list_as_latex().unwrap();
+    assert!(start_list.contains(r"\large 3. & \large \makecell[l]{\\Team 3\\} & \large Kreis 1 \\"));
+
+    let region_result = data.get_region_result_as_latex().unwrap();
+    assert!(region_result.contains(r"\small Platzpunkte"));
+
+    // team 3 has a break in the first batch and starts the second one
+    let team_match_plans = data.get_team_match_plans_as_latex().unwrap();
+    assert!(team_match_plans.contains(r"\small Pause \\"));
+    assert!(team_match_plans.contains(r"\small @1 & \small 1 & \small 3 &"));
+
+    // the breaks get no lane card
+    let lane_match_plans = data
+        .get_lane_match_plans_as_latex(Some("http://192.168.0.2:8080/entry"))
+        .unwrap();
+    assert_eq!(lane_match_plans.matches(r"\barcode").count(), 2);
+    // makebarcode only knows Code 39 and the 2 of 5 codes, Code 39 encodes the digits and dashes of the ids
+    assert!(lane_match_plans.contains(r"\barcode[code=Code39,X=0.35mm,H=1.2cm]{0-0-0}"));
+    assert!(lane_match_plans
+        .contains(r"\qrcode[height=1.5cm]{http://192.168.0.2:8080/entry?match=0-1-0}"));
+
+    // the documents for preparing the offline exports
+    let sample_documents = CompetitionData::get_sample_documents_as_latex().unwrap();
+    assert_eq!(sample_documents.len(), 5);
+    assert!(sample_documents[4].1.contains(r"\qrcode"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_escape_latex() {
+    assert_eq!(escape_latex("SV Eis & Schnee"), r"SV Eis \& Schnee");
+    assert_eq!(escape_latex("Team_1"), r"Team\_1");
+    assert_eq!(escape_latex("100% Stock"), r"100\% Stock");
+    assert_eq!(
+        escape_latex(r"\end{document} #1 $x^2$ ~"),
+        r"\textbackslash{}end\{document\} \#1 \$x\textasciicircum{}2\$ \textasciitilde{}"
+    );
+    assert_eq!(
+        escape_latex("[Gast] Müller\nMeier\u{0}"),
+        "{[}Gast{]} Müller Meier"
+    );
+
+    // no unescaped user input reaches the documents, including player names, officials and the additional text
+    let mut data = CompetitionData::empty();
+    data.name = String::from("Cup {{ 50% }}");
+    data.referee = String::from("Huber & Söhne");
+    data.additional_text = String::from("Startgeld: 20$\n\\input{/etc/passwd}");
+    data.team_distribution = [1, 2];
+    data.teams = Some(vec![vec![
+        Team {
+            name: String::from("SV Eis & Schnee"),
+            region: String::from("Kreis_1"),
+            player_names: [
+                Some(String::from("O'Neil #7")),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            license_number: String::from("BY_12"),
+        },
+        Team {
+            name: String::from("100% Stock"),
+            region: String::from("Kreis_1"),
+            player_names: Default::default(),
+            license_number: String::new(),
+        },
+    ]]);
+    data.current_batch = vec![0];
+    data.matches = vec![vec![Match {
+        team_a: 0,
+        team_b: 1,
+        points: None,
+        result: MatchResult::NotPlayed,
+        batch: 0,
+        lane: 0,
+    }]];
+
+    let documents = [
+        data.get_result_as_latex(&vec![None]).unwrap(),
+        data.get_start_list_as_latex().unwrap(),
+        data.get_region_result_as_latex().unwrap(),
+        data.get_team_match_plans_as_latex().unwrap(),
+        data.get_lane_match_plans_as_latex(None).unwrap(),
+    ];
+    for latex in documents.iter() {
+        assert!(!latex.contains("Eis & Schnee"));
+        assert!(!latex.contains("100% Stock"));
+        assert!(!latex.contains("Kreis_1"));
+        assert!(!latex.contains(r"\input"));
+        assert!(latex.contains(r"SV Eis \& Schnee") || latex.contains(r"100\% Stock"));
+    }
+    assert!(documents[0].contains(r"Cup \{\{ 50\% \}\}"));
+    assert!(documents[0].contains(r"O'Neil \#7"));
+    assert!(documents[2].contains(r"Kreis\_1"));
+
+    // the officials and the additional text are printed at the end of a full page
+    let competition = data.competition_context();
+    assert_eq!(competition.referee, r"Huber \& Söhne");
+    assert_eq!(
+        competition.additional_text_lines,
+        vec![r"Startgeld: 20\$", r"\textbackslash{}input\{/etc/passwd\}"]
+    );
+}
@@ -0,0 +1,125 @@
+use serde_json::{Map, Value};
+
+use super::DEFAULT_BATCH_DURATION_MINUTES;
+
+// The version of the save file format written by this version of ISRAT.
+// Increase it and append a migration to MIGRATIONS whenever CompetitionData changes in an incompatible way.
+pub const CURRENT_FORMAT_VERSION: u64 = 3;
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+// MIGRATIONS[i] migrates a file of version i to version i + 1
+const MIGRATIONS: [Migration; CURRENT_FORMAT_VERSION as usize] =
+    [migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+// migrates the json representation of a save file to the current format version
+pub fn migrate(value: &mut Value) -> Result<(), String> {
+    let object = match value.as_object_mut() {
+        Some(object) => object,
+        None => return Err(String::from("The file does not contain competition data!")),
+    };
+
+    // files written before the versioning was introduced have no version field
+    let version = match object.get("version") {
+        Some(version) => match version.as_u64() {
+            Some(version) => version,
+            None => return Err(format!("Invalid file format version: {version}")),
+        },
+        None => 0,
+    };
+
+    if version > CURRENT_FORMAT_VERSION {
+        return Err(format!(
+            "This file was created by a newer version of ISRAT (file format version {version}, this version supports up to {CURRENT_FORMAT_VERSION}).\nPlease update ISRAT to open it."
+        ));
+    }
+
+    for from_version in version..CURRENT_FORMAT_VERSION {
+        if let Err(msg) = MIGRATIONS[from_version as usize](object) {
+            return Err(format!(
+                "Migration of the file from version {from_version} to {} failed: {msg}",
+                from_version + 1
+            ));
+        }
+    }
+
+    object.insert(String::from("version"), Value::from(CURRENT_FORMAT_VERSION));
+    Ok(())
+}
+
+// version 1 introduced the version field and the region scoring
+fn migrate_v0_to_v1(object: &mut Map<String, Value>) -> Result<(), String> {
+    if !object.contains_key("region_scoring") {
+        object.insert(
+            String::from("region_scoring"),
+            Value::from("PlacementPoints"),
+        );
+    }
+    Ok(())
+}
+
+// version 2 introduced the planned start time and the duration of the batches
+fn migrate_v1_to_v2(object: &mut Map<String, Value>) -> Result<(), String> {
+    if !object.contains_key("start_time") {
+        object.insert(String::from("start_time"), Value::from(""));
+    }
+    if !object.contains_key("batch_duration_minutes") {
+        object.insert(
+            String::from("batch_duration_minutes"),
+            Value::from(DEFAULT_BATCH_DURATION_MINUTES),
+        );
+    }
+    Ok(())
+}
+
+// version 3 introduced the license numbers of the teams
+fn migrate_v2_to_v3(object: &mut Map<String, Value>) -> Result<(), String> {
+    let groups = match object.get_mut("teams") {
+        Some(Value::Array(groups)) => groups,
+        Some(Value::Null) | None => return Ok(()),
+        Some(_) => return Err(String::from("The teams are no list!")),
+    };
+    for team in groups
+        .iter_mut()
+        .filter_map(|group| group.as_array_mut())
+        .flatten()
+    {
+        if let Some(team) = team.as_object_mut() {
+            if !team.contains_key("license_number") {
+                team.insert(String::from("license_number"), Value::from(""));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[test]
+fn test_migrate_unversioned_file() {
+    let mut value: Value = serde_json::from_str(
+        r#"{ "name": "Mustermeisterschaft", "teams": [[{ "name": "Musterteam A" }]] }"#,
+    )
+    .unwrap();
+
+    assert!(migrate(&mut value).is_ok());
+    assert_eq!(value["version"], Value::from(CURRENT_FORMAT_VERSION));
+    assert_eq!(value["region_scoring"], Value::from("PlacementPoints"));
+    assert_eq!(value["start_time"], Value::from(""));
+    assert_eq!(value["teams"][0][0]["license_number"], Value::from(""));
+}
+
+#[cfg(test)]
+#[test]
+fn test_migrate_newer_file() {
+    let mut value: Value = serde_json::from_str(&format!(
+        r#"{{ "version": {}, "name": "Mustermeisterschaft" }}"#,
+        CURRENT_FORMAT_VERSION + 1
+    ))
+    .unwrap();
+
+    let migration_result = migrate(&mut value);
+    assert!(migration_result.is_err());
+    assert!(migration_result
+        .unwrap_err()
+        .contains("newer version of ISRAT"));
+}
@@ -1,39 +1,70 @@
 use chrono::offset::Local;
-use serde::Deserialize;
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::Display;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
-use std::thread::{self, JoinHandle};
-use tectonic::config::PersistentConfig;
-use tectonic::driver::{OutputFormat, ProcessingSessionBuilder};
-use tectonic::status::NoopStatusBackend;
 
 use crate::data::read_write::save_to_file;
 
-use self::read_write::read_from_file;
-
+use self::archive::{extract_archive_files, is_archive};
+use self::export_jobs::{ExportJobs, TexBundle};
+use self::ics_export::calendar_file_name;
+use self::journal::{read_journal, record_event, replay, start_journal, JournalEvent, LaneResult};
+use self::migration::CURRENT_FORMAT_VERSION;
+use self::read_write::{is_database, read_from_file};
+use self::season::SeasonData;
+
+pub mod archive;
+pub mod autosave;
+pub mod csv_export;
+pub mod export_jobs;
+pub mod federation_export;
+pub mod html_export;
+pub mod ics_export;
+pub mod journal;
+pub mod latex_export;
+pub mod migration;
 pub mod read_write;
+pub mod season;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod team_import;
+pub mod templates;
+pub mod xlsx_export;
 
 pub struct Competition {
     pub data: Option<CompetitionData>,
-    pub spawned_threads: Vec<JoinHandle<()>>, // stores the join handles to the threads used to export pdf and html documents
+    pub export_jobs: ExportJobs, // the running and finished PDF exports
     pub current_interim_result: Vec<Option<Vec<InterimResultEntry>>>, // a ResultEntry vector for each group in descending order
     pub absolute_dir_path: Option<PathBuf>, // absolute path to the folder to store the export documents and autosaves
     pub absolute_file_path: Option<PathBuf>, // absolute path to the data file, must not be in absolute_dir_path
+    pub saved_data_hash: Option<u64>, // hash of the data when the data file was last opened or saved
 }
 
 impl Competition {
     pub fn empty() -> Self {
         Competition {
             data: None,
-            spawned_threads: vec![],
+            export_jobs: ExportJobs::new(),
             current_interim_result: vec![],
             absolute_dir_path: None,
             absolute_file_path: None,
+            saved_data_hash: None,
         }
     }
 
+    // hash of the serialized competition data, used to detect changes
+    pub fn data_hash(&self) -> Option<u64> {
+        let json_string = self.data.as_ref()?.get_as_json_string().ok()?;
+        let mut hasher = DefaultHasher::new();
+        json_string.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     pub fn get_current_interim_result_for_group(
         &mut self,
         group_idx: usize,
@@ -67,8 +98,6 @@ impl Competition {
             Err(msg) => return Err(msg),
         };
 
-        self.data = Some(competition_data);
-
         self.absolute_dir_path = match path.parent() {
             Some(parent_path) => Some(parent_path.to_path_buf()),
             None => {
@@ -78,12 +107,28 @@ impl Competition {
             }
         };
 
+        // make the exports and the audit trail of a handed over archive available
+        if is_archive(&path) {
+            extract_archive_files(&path)?;
+        }
+
+        // rebuild the changes which were journaled after the last save
+        let journal_entries = read_journal(&path)?;
+        let replayed_data = replay(&journal_entries, &competition_data)?;
+
+        self.data = Some(competition_data);
+        self.saved_data_hash = self.data_hash();
+        self.data = Some(replayed_data);
+
+        if let Err(msg) = start_journal(&path, self.data.as_ref().unwrap()) {
+            eprintln!("[handle_open_file]: {msg}");
+        }
         self.absolute_file_path = Some(path);
 
         Ok(())
     }
 
-    pub fn handle_save_file(&mut self, mut path: PathBuf) -> Result<(), String> {
+    pub fn handle_save_file(&mut self, mut path: PathBuf, backup_count: u32) -> Result<(), String> {
         if path.exists() && path.is_dir() {
             return Err(String::from("Path references a directory!"));
         }
@@ -95,8 +140,10 @@ impl Competition {
             return Err(String::from("No competition data available!"));
         }
 
-        // adjust file name to have the right extension
-        path.set_extension("json");
+        // adjust file name to have the right extension, archives and databases keep theirs
+        if !is_archive(&path) && !is_database(&path) {
+            path.set_extension("json");
+        }
 
         // update path
         self.absolute_file_path = Some(path.clone());
@@ -107,94 +154,283 @@ impl Competition {
             .parent()
             .map(|path| path.to_path_buf());
 
-        save_to_file(path, self.data.as_ref().unwrap())
+        save_to_file(path, self.data.as_ref().unwrap(), backup_count)?;
+        self.saved_data_hash = self.data_hash();
+
+        // the journaled changes are part of the file now, a failed marker only leads to a redundant replay
+        if let Err(msg) = self.record_journal_event(JournalEvent::Saved) {
+            eprintln!("[handle_save_file]: {msg}");
+        }
+        Ok(())
     }
 
-    pub fn export_result_list(&mut self) {
+    // replaces the competition data by the content of the backup, but keeps the path of the competition file
+    pub fn handle_restore_backup(&mut self, backup_path: PathBuf) -> Result<(), String> {
+        let competition_data = read_from_file(backup_path)?;
+        self.handle_restore_state(competition_data)
+    }
+
+    // replaces the competition data, e.g. by a state of the journal history, the journal continues from this state
+    pub fn handle_restore_state(
+        &mut self,
+        competition_data: CompetitionData,
+    ) -> Result<(), String> {
+        self.data = Some(competition_data);
+        self.current_interim_result = vec![];
+        self.record_journal_snapshot()
+    }
+
+    // writes the complete data to the journal, used for changes without an event of their own like the setup
+    pub fn record_journal_snapshot(&mut self) -> Result<(), String> {
+        match self.data.as_ref() {
+            Some(data) => {
+                let snapshot = JournalEvent::snapshot(data)?;
+                self.record_journal_event(snapshot)
+            }
+            None => Ok(()),
+        }
+    }
+
+    // writes the change to the journal of the competition file, changes of never saved competitions are not journaled
+    pub fn record_journal_event(&mut self, event: JournalEvent) -> Result<(), String> {
+        match (self.absolute_file_path.as_ref(), self.data.as_ref()) {
+            (Some(path), Some(data)) => record_event(path, data, event),
+            _ => Ok(()),
+        }
+    }
+
+    // the PDF exports fail if a template can not be rendered, the LaTeX engine runs as export job
+    pub fn export_result_list(&mut self, tex_bundle: TexBundle) -> Result<(), String> {
         debug_assert!(self.data.is_some());
         self.current_interim_result = self.data.as_mut().unwrap().calc_all_interim_result();
+        let latex = self
+            .data
+            .as_ref()
+            .unwrap()
+            .get_result_as_latex(&self.current_interim_result)?;
         self.export_pdf(
             format!("result-{}", Local::now().format("%Y%m%d-%H%M")),
-            self.data
-                .as_ref()
-                .unwrap()
-                .get_result_as_latex(&self.current_interim_result),
+            latex,
+            tex_bundle,
         );
+        Ok(())
     }
 
-    pub fn export_start_list(&mut self) {
+    pub fn export_start_list(&mut self, tex_bundle: TexBundle) -> Result<(), String> {
         debug_assert!(self.data.is_some());
+        let latex = self.data.as_ref().unwrap().get_start_list_as_latex()?;
         self.export_pdf(
             format!("startlist-{}", Local::now().format("%Y%m%d-%H%M")),
-            self.data.as_ref().unwrap().get_start_list_as_latex(),
+            latex,
+            tex_bundle,
         );
+        Ok(())
     }
 
-    pub fn export_team_match_plans(&mut self) {
+    pub fn export_team_match_plans(&mut self, tex_bundle: TexBundle) -> Result<(), String> {
         debug_assert!(self.data.is_some());
+        let latex = self
+            .data
+            .as_ref()
+            .unwrap()
+            .get_team_match_plans_as_latex()?;
         self.export_pdf(
             format!("team_matchplans-{}", Local::now().format("%Y%m%d-%H%M")),
-            self.data.as_ref().unwrap().get_team_match_plans_as_latex(),
+            latex,
+            tex_bundle,
         );
+        Ok(())
     }
 
-    pub fn export_lane_match_plans(&mut self) {
+    pub fn export_region_result(&mut self, tex_bundle: TexBundle) -> Result<(), String> {
         debug_assert!(self.data.is_some());
+        let latex = self.data.as_ref().unwrap().get_region_result_as_latex()?;
+        self.export_pdf(
+            format!("region_result-{}", Local::now().format("%Y%m%d-%H%M")),
+            latex,
+            tex_bundle,
+        );
+        Ok(())
+    }
+
+    pub fn export_season_table(
+        &mut self,
+        season_path: PathBuf,
+        tex_bundle: TexBundle,
+    ) -> Result<(), String> {
+        let season = SeasonData::read_from_file(&season_path)?;
+        let matchdays = season.load_matchdays(&season_path)?;
+        let latex = season.get_season_result_as_latex(&matchdays)?;
+        self.export_pdf(
+            format!("season-{}", Local::now().format("%Y%m%d-%H%M")),
+            latex,
+            tex_bundle,
+        );
+        Ok(())
+    }
+
+    // entry_url is the address of the result entry page if the live results server is running
+    pub fn export_lane_match_plans(
+        &mut self,
+        entry_url: Option<String>,
+        tex_bundle: TexBundle,
+    ) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        let latex = self
+            .data
+            .as_ref()
+            .unwrap()
+            .get_lane_match_plans_as_latex(entry_url.as_deref())?;
         self.export_pdf(
             format!("lane_matchplans-{}", Local::now().format("%Y%m%d-%H%M")),
-            self.data.as_ref().unwrap().get_lane_match_plans_as_latex(),
+            latex,
+            tex_bundle,
         );
+        Ok(())
     }
 
-    fn export_pdf(&mut self, filename: String, latex: String) {
-        self.verify_paths();
-        let dir_path = self.absolute_dir_path.as_ref().unwrap().clone();
-        self.spawned_threads.push(thread::spawn(move || {
-            // TODO: Remove for productive builds
-            #[cfg(debug_assertions)]
-            {
-                println!("{}", latex);
-            }
+    // the machine-readable results for the rankings of the federation, validated before writing
+    pub fn export_federation_results(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        self.export_file(
+            format!(
+                "federation-results-{}.json",
+                Local::now().format("%Y%m%d-%H%M")
+            ),
+            self.data
+                .as_ref()
+                .unwrap()
+                .get_federation_results_as_json()?
+                .as_bytes(),
+        )
+    }
 
-            let mut status = NoopStatusBackend::default();
-            let config = PersistentConfig::open(false)
-                .expect("failed to open the default configuration file");
+    pub fn export_standings_csv(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        self.export_file(
+            format!("standings-{}.csv", Local::now().format("%Y%m%d-%H%M")),
+            self.data
+                .as_ref()
+                .unwrap()
+                .get_standings_as_csv()?
+                .as_bytes(),
+        )
+    }
 
-            let bundle = config
-                .default_bundle(false, &mut status)
-                .expect("failed to load the default resource bundle");
+    pub fn export_schedule_csv(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        self.export_file(
+            format!("schedule-{}.csv", Local::now().format("%Y%m%d-%H%M")),
+            self.data
+                .as_ref()
+                .unwrap()
+                .get_schedule_as_csv()?
+                .as_bytes(),
+        )
+    }
 
-            let format_cache_path = config
-                .format_cache_path()
-                .expect("failed to set up the format cache");
+    pub fn export_results_csv(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        self.export_file(
+            format!("results-{}.csv", Local::now().format("%Y%m%d-%H%M")),
+            self.data.as_ref().unwrap().get_results_as_csv()?.as_bytes(),
+        )
+    }
+
+    // writes one calendar for each group and each team into the calendars folder of the exports
+    pub fn export_calendars(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        let calendar_dir_path = match self.absolute_dir_path.as_ref() {
+            Some(dir_path) => dir_path.join("exports").join("calendars"),
+            None => return Err(String::from("Save the competition before exporting it.")),
+        };
+
+        let data = self.data.as_ref().unwrap();
+        let mut calendars = vec![];
+        for (group_idx, teams) in data.teams.as_ref().unwrap().iter().enumerate() {
+            let group_name = data.group_name(group_idx);
+            calendars.push((
+                calendar_file_name(&group_name),
+                data.get_group_calendar_as_ics(group_idx)?,
+            ));
+            for (team_idx, team) in teams.iter().enumerate() {
+                calendars.push((
+                    calendar_file_name(&format!("{group_name}-{}-{}", team_idx + 1, team.name)),
+                    data.get_team_calendar_as_ics(group_idx, team_idx)?,
+                ));
+            }
+        }
+
+        if fs::create_dir_all(&calendar_dir_path).is_err() {
+            return Err(String::from("Calendar directory creation failed."));
+        }
+        for (file_name, content) in calendars {
+            if fs::write(calendar_dir_path.join(format!("{file_name}.ics")), content).is_err() {
+                return Err(format!("Write to {file_name}.ics failed!"));
+            }
+        }
+        Ok(())
+    }
+
+    // writes the website into its own folder, the pages of a previous export are overwritten
+    pub fn export_website(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        let website_dir_path = match self.absolute_dir_path.as_ref() {
+            Some(dir_path) => dir_path.join("exports").join("website"),
+            None => return Err(String::from("Save the competition before exporting it.")),
+        };
 
-            let mut sb = ProcessingSessionBuilder::default();
-            let export_dir_path = dir_path.join("exports");
+        if fs::create_dir_all(&website_dir_path).is_err() {
+            return Err(String::from("Website directory creation failed."));
+        }
 
-            if !export_dir_path.exists() {
-                fs::create_dir(&export_dir_path).expect("Export directory creation failed.");
-            } else if !export_dir_path.is_dir() {
-                // TODO: handle this more beautiful, e.g. popup message
-                panic!(r#""exports" exists, but is no directory!"#);
+        for page in self.data.as_ref().unwrap().get_result_as_html() {
+            if fs::write(website_dir_path.join(&page.file_name), page.content).is_err() {
+                return Err(format!("Write to {} failed!", page.file_name));
             }
+        }
+        Ok(())
+    }
+
+    pub fn export_xlsx(&self) -> Result<(), String> {
+        debug_assert!(self.data.is_some());
+        self.export_file(
+            format!("competition-{}.xlsx", Local::now().format("%Y%m%d-%H%M")),
+            &self.data.as_ref().unwrap().get_as_xlsx()?,
+        )
+    }
+
+    // writes the content to the exports folder next to the competition file
+    fn export_file(&self, filename: String, content: &[u8]) -> Result<(), String> {
+        let export_dir_path = match self.absolute_dir_path.as_ref() {
+            Some(dir_path) => dir_path.join("exports"),
+            None => return Err(String::from("Save the competition before exporting it.")),
+        };
+
+        if fs::create_dir_all(&export_dir_path).is_err() {
+            return Err(String::from("Export directory creation failed."));
+        }
 
-            sb.bundle(bundle)
-                .primary_input_buffer(latex.as_bytes())
-                .tex_input_name(format!("{}.tex", filename).as_str())
-                .format_name("latex")
-                .format_cache_path(format_cache_path)
-                .keep_logs(false)
-                .keep_intermediates(false)
-                .print_stdout(false)
-                .output_format(OutputFormat::Pdf)
-                .output_dir(export_dir_path);
-
-            let mut sess = sb
-                .create(&mut status)
-                .expect("failed to initialize the LaTeX processing session");
-            sess.run(&mut status).expect("the LaTeX engine failed");
-            println!("Finished export!");
-        }));
+        match fs::write(export_dir_path.join(&filename), content) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!("Write to {filename} failed!")),
+        }
+    }
+
+    fn export_pdf(&mut self, filename: String, latex: String, tex_bundle: TexBundle) {
+        self.verify_paths();
+        let export_dir_path = self.absolute_dir_path.as_ref().unwrap().join("exports");
+        self.export_jobs
+            .start_pdf_export(filename, export_dir_path, latex, tex_bundle);
+    }
+
+    // renders sample documents with all packages and fonts of the templates, so that later exports work offline
+    pub fn prepare_offline_exports(&mut self, tex_bundle: TexBundle) -> Result<(), String> {
+        let documents = CompetitionData::get_sample_documents_as_latex()?;
+        let work_dir_path = std::env::temp_dir().join(format!("israt-tex-{}", std::process::id()));
+        self.export_jobs
+            .start_offline_preparation(documents, work_dir_path, tex_bundle);
+        Ok(())
     }
 
     fn verify_paths(&self) {
@@ -215,7 +451,15 @@ impl Competition {
     }
 }
 
-#[derive(Clone, Deserialize)]
+// the content of a save file, the format version is stored next to the competition data
+#[derive(Serialize)]
+struct SaveFile<'a> {
+    version: u64,
+    #[serde(flatten)]
+    data: &'a CompetitionData,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CompetitionData {
     pub name: String,
     pub date_string: String,
@@ -233,6 +477,18 @@ pub struct CompetitionData {
     pub matches: Vec<Vec<Match>>,    // a Match vector for each group
     pub current_batch: Vec<u32>,     // the current batch of matches played for each group
     pub with_break: bool, // defines whether theres a break for the teams, only important for a even team count
+    #[serde(default)]
+    pub region_scoring: RegionScoring, // defines how the results of the teams are accumulated for the region ranking
+    #[serde(default)]
+    pub start_time: String, // the planned start of the first batch as "HH:MM", empty if no times are planned
+    #[serde(default = "default_batch_duration")]
+    pub batch_duration_minutes: u32, // the planned time for one batch including the change of the lanes
+}
+
+pub const DEFAULT_BATCH_DURATION_MINUTES: u32 = 30;
+
+fn default_batch_duration() -> u32 {
+    DEFAULT_BATCH_DURATION_MINUTES
 }
 
 impl CompetitionData {
@@ -254,9 +510,22 @@ impl CompetitionData {
             matches: vec![],
             current_batch: vec![],
             with_break: true,
+            region_scoring: RegionScoring::PlacementPoints,
+            start_time: String::from(""),
+            batch_duration_minutes: DEFAULT_BATCH_DURATION_MINUTES,
         }
     }
 
+    // the planned start of a batch, None if the date or the start time is missing or invalid
+    pub fn batch_start_time(&self, batch: u32) -> Option<NaiveDateTime> {
+        let date = NaiveDate::parse_from_str(self.date_string.trim(), "%d.%m.%Y").ok()?;
+        let start_time = NaiveTime::parse_from_str(self.start_time.trim(), "%H:%M").ok()?;
+        Some(
+            date.and_time(start_time)
+                + Duration::minutes(batch as i64 * self.batch_duration_minutes as i64),
+        )
+    }
+
     fn calc_all_interim_result(&mut self) -> Vec<Option<Vec<InterimResultEntry>>> {
         (0..self.teams.as_ref().unwrap().len())
             .map(|group_idx| Some(self.calc_interim_result_for_group(group_idx)))
@@ -357,6 +626,77 @@ impl CompetitionData {
         table
     }
 
+    pub fn calc_region_result(&self) -> Vec<RegionResultEntry> {
+        let mut table: Vec<RegionResultEntry> = vec![];
+
+        // accumulate the interim results of all teams for their region, teams without region are ignored
+        (0..self.teams.as_ref().unwrap().len()).for_each(|group_idx| {
+            let group_result = self.calc_interim_result_for_group(group_idx);
+            let group_size = group_result.len() as i32;
+
+            group_result.iter().enumerate().for_each(|(rank, i_res)| {
+                // the region is typed in for each team, so surrounding whitespace must not split it up
+                let region = self.teams.as_ref().unwrap()[group_idx][i_res.team_idx]
+                    .region
+                    .trim();
+                if region.is_empty() {
+                    return;
+                }
+
+                let entry = match table.iter().position(|entry| entry.region == region) {
+                    Some(entry_idx) => &mut table[entry_idx],
+                    None => {
+                        table.push(RegionResultEntry {
+                            region: region.to_string(),
+                            count_teams: 0,
+                            points: 0,
+                            match_points: [0, 0],
+                            stock_points: [0, 0],
+                            quotient: 0.0,
+                        });
+                        table.last_mut().unwrap()
+                    }
+                };
+
+                entry.count_teams += 1;
+                entry.points += match self.region_scoring {
+                    // the winner of a group gets as many points as there are teams in the group, the last team gets 1 point
+                    RegionScoring::PlacementPoints => group_size - rank as i32,
+                    RegionScoring::MatchPoints => i_res.match_points[0],
+                };
+                entry.match_points[0] += i_res.match_points[0];
+                entry.match_points[1] += i_res.match_points[1];
+                entry.stock_points[0] += i_res.stock_points[0];
+                entry.stock_points[1] += i_res.stock_points[1];
+            });
+        });
+
+        // calculate quotient
+        table.iter_mut().for_each(|entry| {
+            entry.quotient = if entry.stock_points[0] == 0 {
+                0.0
+            } else {
+                (entry.stock_points[0] as f32) / (entry.stock_points[1] as f32)
+            };
+        });
+
+        // sort the table, ties are broken by the quotient and the stock point difference like in the group results
+        table.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(
+                    b.quotient
+                        .partial_cmp(&a.quotient)
+                        .unwrap_or(Ordering::Equal),
+                )
+                .then(
+                    (b.stock_points[0] - b.stock_points[1])
+                        .cmp(&(a.stock_points[0] - a.stock_points[1])),
+                )
+        });
+        table
+    }
+
     pub fn generate_matches(&mut self) {
         assert!(self.matches.is_empty());
 
@@ -451,637 +791,103 @@ impl CompetitionData {
         }
     }
 
-    pub fn get_result_as_html(&self) -> String {
-        /*format!(
-            r#"<html><head> This is sparta! {}<\head> <\html>"#,
-            self.name
-        )*/
-        todo!();
-    }
-
-    fn get_header_as_latex(&self) -> String {
-        format!(
-            r"\begin{{center}}
-            \large \textbf{{
-            {}\\ {}\\ am {}\\ {} \\ Durchführer: {}
-            }}
-            \end{{center}}
-            \par\noindent\rule{{\linewidth}}{{0.4pt}}
-            \footnotesize ISRAT: \href{{https://www.github.com/Explosiontime202/ISRAT}}{{https://www.github.com/Explosiontime202/ISRAT}}
-            \hfill
-            \footnotesize {}
-            ",
-            self.organizer,
-            self.name,
-            self.date_string,
-            self.place,
-            self.executor,
-            Local::now().format("%d.%m.%Y %H:%M"),
-        )
-    }
-
-    fn get_result_as_latex(
+    // Checks the results for the current batch of a group before they are submitted. The GUI and the REST API
+    // share this check, so that both accept the same results.
+    pub fn check_batch_results(
         &self,
-        current_interim_result: &Vec<Option<Vec<InterimResultEntry>>>,
-    ) -> String {
-        // TODO: make this configurable by the user
-        let player_names_until = 3; // index of the first team which has NO player names displayed
-
-        let header = self.get_header_as_latex();
-
-        // analyse if all matches were played
-        let is_final_result = self
-            .matches
+        group_idx: usize,
+        results: &[LaneResult],
+    ) -> Result<(), String> {
+        let matches = match self.matches.get(group_idx) {
+            Some(matches) => matches,
+            None => return Err(format!("There is no group {}!", group_idx + 1)),
+        };
+        let current_batch = self.current_batch[group_idx];
+        let batch_matches: Vec<&Match> = matches
             .iter()
-            .map(|group_matches| {
-                group_matches
-                    .iter()
-                    .all(|_match| _match.result != MatchResult::NotPlayed)
-            })
-            .all(|b| b);
-
-        let footnote = format!(
-            r"
-        \vspace{{1cm}}
-        \begin{{center}}
-        \normalsize
-            {}\\
-        \end{{center}}
-        \vfill
-        \par\noindent\rule{{\linewidth}}{{0.4pt}}
-        \large
-        \begin{{center}}
-            \begin{{tabular}}{{
-                >{{\centering\arraybackslash}} p{{0.333\tablewidth}}
-                >{{\centering\arraybackslash}} p{{0.333\tablewidth}}
-                >{{\centering\arraybackslash}} p{{0.333\tablewidth}}}}
-                {} & {} & {} \\
-                {{[Schiedsrichter]}} & [Wettbewerbsleiter] & [Schriftführer]
-            \end{{tabular}}
-        \end{{center}}
-        \clearpage
-        ",
-            self.additional_text.replace("\n", r"\\"),
-            self.referee,
-            self.competition_manager,
-            self.clerk
-        );
-
-        let mut count_teams_with_name = 0;
-        let mut count_teams_without_name = 0;
-        let mut previous_new_page = true; // determines whether the header is printed, for first team true
-
-        let groups = self.group_names.as_ref().unwrap().iter().enumerate().map(|(group_idx, group_name)| {
-            assert!(current_interim_result[group_idx].is_some());
-            let team_names = &self.teams.as_ref().unwrap()[group_idx];
-            let group_result = current_interim_result[group_idx].as_ref().unwrap().iter().enumerate().map(|(rank, i_res)| {
-                let team = &team_names[i_res.team_idx];
-                let display_player_names = rank < player_names_until;
-
-                // join player names, separate by ", ", but if no player name is given, enter "~" to force latex to actually draw the newline
-                let player_names = if !display_player_names {
-                    count_teams_without_name += 1;
-                    String::from("")
-                } else {
-                    count_teams_with_name += 1;
-                    if team.player_names.iter().all(|x| x.is_none()) {
-                        String::from("~")
-                    } else {team.player_names.iter()
-                        .filter_map(|name_opt| name_opt.as_ref().map(|name| {name.as_str()}))
-                        .collect::<Vec<&str>>()
-                        .join(", ")
-                    }
-                };
+            .filter(|_match| _match.batch == current_batch && _match.result != MatchResult::Break)
+            .collect();
 
-                format!(r"\large {}. & \large \makecell[l]{{{}{}{}}} & \large {} & \large {} & \large {} & \large {:.3} & \large {} & \large {} \\
-                ",
-                rank + 1,
-                if display_player_names {r"\\"} else {""},
-                team.name,
-                if display_player_names {format!(r"\\ \footnotesize {}", player_names)} else {String::from("")},
-                team.region,
-                i_res.match_points[0],
-                i_res.match_points[1],
-                i_res.quotient,
-                i_res.stock_points[0],
-                i_res.stock_points[1])
-            }).collect::<Vec<String>>().join("");
-
-            format!(r"
-            {}
-            \begin{{center}}
-                \LARGE \textbf{{{} {group_name}}}
-                \par
-                \small
-                \begin{{tabular}}{{
-                    >{{\centering\arraybackslash}}p{{0.0833\tablewidth}}
-		            >{{\raggedright\arraybackslash}}p{{0.5\tablewidth}}
-		            >{{\centering\arraybackslash}} p{{0.0833\tablewidth}}
-		            >{{\raggedleft\arraybackslash}}p{{\columnspielpunkte-2\tabcolsep}}@{{\large ~:~}}
-		            >{{\raggedright\arraybackslash}}p{{\columnspielpunkte-2\tabcolsep}}
-		            >{{\centering\arraybackslash}}p{{0.0833\tablewidth}}
-		            >{{\raggedleft\arraybackslash}}p{{\columnstockpunkte-2\tabcolsep}}@{{\large ~:~}}
-		            >{{\raggedright\arraybackslash}}p{{\columnstockpunkte-2\tabcolsep}}
-                }}
-                \small Rang & \small Mannschaft & \small Kreis & \multicolumn{{2}}{{c}}{{\small Punkte}} & \small Quotient & \multicolumn{{2}}{{c}}{{\small Stockpunkte}} \\
-                {}
-            \end{{tabular}}
-            \end{{center}}
-            {}
-        ",
-        if previous_new_page {header.as_str()} else {""},
-        if is_final_result {format!("Ergebnisliste {group_name}")} else {format!("Zwischenliste {group_name} nach Spiel {}", self.current_batch[group_idx])},
-        group_result,
-        if 3 * count_teams_with_name + count_teams_without_name > 16 {
-            previous_new_page = true;
-            footnote.as_str()
-        } else {
-            previous_new_page = false;
-            r"\vspace{0.5cm}"
+        if batch_matches.is_empty() {
+            return Err(String::from("All matches of this group have been played."));
         }
-        )})
-        .collect::<Vec<String>>()
-        .join("");
-
-        format!(
-            r"\documentclass{{article}}
-
-    \usepackage{{array}}
-    \usepackage{{calc}}
-    \usepackage{{fontspec}}
-    \usepackage{{geometry}}
-    \usepackage{{hyperref}}
-    \usepackage{{makecell}}
-    \usepackage{{multirow}}
-    \usepackage{{tabularx}}
-    
-    \setlength{{\oddsidemargin}}{{-40pt}}
-    \setlength{{\textwidth}}{{532pt}}
-    \newlength{{\tablewidth}}
-    \setlength{{\tablewidth}}{{0.8\textwidth}}
-
-    \newlength{{\columnstockpunkte}}
-    \setlength{{\columnstockpunkte}}{{\widthof{{Stockpunkte}}}}
-
-    \newlength{{\columnspielpunkte}}
-    \setlength{{\columnspielpunkte}}{{\widthof{{Punkte}}}}
-
-    \geometry{{
-        a4paper,
-        total={{190mm,257mm}},
-        left=10mm,
-        top=7.5mm,
-        bottom=10mm
-        }}
-    \setmainfont{{FreeSans}}
-    \pagenumbering{{gobble}}
-    \begin{{document}}
-        {}
-    \end{{document}}",
-            groups
-        )
-    }
-
-    fn get_start_list_as_latex(&self) -> String {
-        let header = self.get_header_as_latex();
-
-        let mut previous_new_page = true; // determines whether the header is printed, for first team true
 
-        assert!(self.group_names.is_some());
-        assert!(self.teams.is_some());
-
-        let groups = self
-            .teams
-            .as_ref()
-            .unwrap()
-            .iter()
-            .enumerate()
-            .map(|(group_idx, teams_in_group)| {
-                let group_result = teams_in_group
-                    .iter()
-                    .enumerate()
-                    .map(|(rank, team)| {
-                        format!(
-                            r"\large {}. & \large \makecell[l]{{\\{}\\}} & \large {} \\
-                ",
-                            rank + 1,
-                            team.name,
-                            team.region,
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join("");
-
-                format!(
-                    r"
-            {}
-            \begin{{center}}
-                \LARGE \textbf{{Startliste {}}}
-                \par
-                \small
-                \begin{{tabular}}{{
-                    >{{\centering\arraybackslash}}p{{0.125\tablewidth}}
-		            >{{\raggedright\arraybackslash}}p{{0.75\tablewidth}}
-		            >{{\centering\arraybackslash}} p{{0.125\tablewidth}}
-                }}
-                \small Startnummer & \small Mannschaft & \small Kreis \\
-                {}
-            \end{{tabular}}
-            \end{{center}}
-            {}
-        ",
-                    if previous_new_page {
-                        header.as_str()
-                    } else {
-                        ""
-                    },
-                    self.group_names.as_ref().unwrap()[group_idx],
-                    group_result,
-                    if self.team_distribution[1] > 15 {
-                        previous_new_page = true;
-                        ""
-                    } else {
-                        previous_new_page = false;
-                        r"\vspace{0.5cm}"
-                    }
-                )
-            })
-            .collect::<Vec<String>>()
-            .join("");
-
-        format!(
-            r"\documentclass{{article}}
-
-            \usepackage{{array}}
-            \usepackage{{calc}}
-            \usepackage{{fontspec}}
-            \usepackage{{geometry}}
-            \usepackage{{hyperref}}
-            \usepackage{{makecell}}
-            \usepackage{{multirow}}
-            \usepackage{{tabularx}}
-            
-            \setlength{{\oddsidemargin}}{{-40pt}}
-            \setlength{{\textwidth}}{{532pt}}
-            \newlength{{\tablewidth}}
-            \setlength{{\tablewidth}}{{0.8\textwidth}}
-
-            \geometry{{
-                a4paper,
-                total={{190mm,257mm}},
-                left=10mm,
-                top=7.5mm,
-                bottom=10mm
-            }}
-            \setmainfont{{FreeSans}}
-            \pagenumbering{{gobble}}
-            \begin{{document}}
-            {}
-            \end{{document}}",
-            groups
-        )
-    }
-
-    fn get_team_match_plans_as_latex(&self) -> String {
-        let matchplans = self.teams.as_ref().unwrap().iter().enumerate().map(|(group_idx, group)| {
-            format!("
-                {}
-                ",
-                group.iter().enumerate().map(|(team_idx, team)| {
-                    let mut matches : Vec<&Match> =
-                        self.matches[group_idx]
-                        .iter()
-                        .filter(|&_match| {
-                            _match.team_a == team_idx || _match.team_b == team_idx
-                        })
-                        .collect();
-
-                    // TODO: Is matcehes vector already sorted, e.g. is sorting necessary?
-                    matches.sort_by(|a, b| { a.batch.cmp(&b.batch) });
-
-                    let matches_string =
-                        matches
-                        .iter()
-                        .map(|_match| {
-                            if _match.result == MatchResult::Break {
-                                String::from(r"\rule[3pt]{\dimexpr0.8\textwidth - \tabcolsep}{0.4pt} & & & & & & & & & & & & & & & & & & & \small Pause \\
-        ")
-                            } else {
-                            let (opponent_idx, start_of_match) = if _match.team_a == team_idx {(_match.team_b, true)} else {(_match.team_a, false)};
-                                format!(
-        r"
-        \small {} & \small {} & \small {} & & & & & & & & & & & & & & & & & \small {} \\
-        ",
-                                format!("{}{}", if start_of_match {"@"} else {""}, opponent_idx + 1),
-                                _match.lane + 1,
-                                _match.team_a + 1,
-                                self.teams.as_ref().unwrap()[group_idx][opponent_idx].name
-                                )
-                            }
-                        }).collect::<Vec<String>>().join(r"\hline");
-
-                    format!(
-    r#"
-    \begin{{tabularx}}{{\textwidth}}{{
-        *{{3}}{{|>{{\centering\arraybackslash\hsize=0.06\hsize}}X}}
-        *{{8}}{{|>{{\centering\arraybackslash\hsize=0.0325\hsize}}X}}
-        "
-        *{{8}}{{>{{\centering\arraybackslash\hsize=0.0325\hsize}}X|}}
-        >{{\centering\arraybackslash\hsize=0.3\hsize}}X|
-    }}
-        \multicolumn{{20}}{{>{{\hsize=\dimexpr\textwidth-2\tabcolsep-2\arrayrulewidth\relax}}X}}{{\small {}. {}}} \\
-        \hline
-        \small Geg. & \small Bahn & \small Ans & \multicolumn{{16}}{{>{{\hsize=\dimexpr0.4875\hsize\relax}}X|}}{{~}} & \small Verein  \\
-        \hline
-        {matches_string}
-        \hline
-    \end{{tabularx}}"#,
-                        team_idx + 1,
-                        team.name
-                    )
-                }).collect::<Vec<String>>().join(r"\\[2cm]
-            "))
-        }).collect::<Vec<String>>().join(r"\\[2cm]
-        ");
-        format!(
-            r#"
-    \documentclass{{article}}
-
-    \usepackage[a4paper]{{geometry}}
-    \usepackage{{fontspec}}
-    \usepackage{{tabularx}}
-
-    \geometry{{
-        a4paper,
-        total={{200mm,290mm}},
-        left=0mm,
-        top=2mm,
-    }}
-
-    \newcommand{{\thickhline}}{{
-        \noalign {{\ifnum 0=`}}\fi \hrule height 1.5pt
-        \futurelet \reserved@a \@xhline
-    }}
-    \newcolumntype{{"}}{{@{{\hskip\tabcolsep\vrule width 1.5pt\hskip\tabcolsep}}}}
-
-    \setmainfont{{FreeSans}}
-    \pagenumbering{{gobble}}
-
-    \begin{{document}}
-    \Large
-    
-    {matchplans}
-
-    \end{{document}}
-    "#
-        )
-    }
+        for (idx, result) in results.iter().enumerate() {
+            if !batch_matches
+                .iter()
+                .any(|_match| _match.lane == result.lane)
+            {
+                return Err(format!("There is no match on lane {}!", result.lane + 1));
+            }
+            if result.points.iter().any(|points| *points < 0) {
+                return Err(format!(
+                    "The points of lane {} must not be negative!",
+                    result.lane + 1
+                ));
+            }
+            if results[..idx].iter().any(|other| other.lane == result.lane) {
+                return Err(format!(
+                    "The result of lane {} is given twice!",
+                    result.lane + 1
+                ));
+            }
+        }
 
-    fn get_lane_match_plans_as_latex(&self) -> String {
-        let matchplans = self
-            .matches
+        if batch_matches
             .iter()
-            .enumerate()
-            .map(|(group_idx, group_matches)| {
-                let group_name = &self.group_names.as_ref().unwrap()[group_idx];
-                group_matches
-                .iter()
-                .filter(|&_match| _match.result != MatchResult::Break)
-                .map(|_match| {
-                    let team_a_name = &self.teams.as_ref().unwrap()[group_idx][_match.team_a].name;
-                    let team_b_name = &self.teams.as_ref().unwrap()[group_idx][_match.team_b].name;
-                    format!(
-r"
-    \LARGE
-    \begin{{tabularx}}{{\textwidth}}{{
-        |>{{\centering\arraybackslash\hsize=0.1\hsize}}X
-        *{{6}}{{|>{{\centering\arraybackslash\hsize=0.0458\hsize}}X}}
-        |>{{\centering\arraybackslash\hsize=0.1\hsize}}X
-        |
-        *{{6}}{{>{{\centering\arraybackslash\hsize=0.0458\hsize}}X|}}
-        >{{\centering\arraybackslash\hsize=0.1\hsize}}X|
-        >{{\centering\arraybackslash\hsize=0.15\hsize}}X|
-        }}
-        \hline
-        \multicolumn{{8}}{{|l|}}{{\large \textbf{{{}. {}}}}} & \multicolumn{{8}}{{r|}}{{\large \textbf{{{}. {}}}}} \\
-        \hline
-        & \small 1 & \small 2 & \small 3 & \small 4 & \small 5 & \small 6 & \small Summe & \small 1 & \small 2 & \small 3 & \small 4 & \small 5 & \small 6 & \small Summe & \small Anspiel {} \\
-        \hline
-        + & & & & & & & & & & & & & & & \small  Bahn {} \\
-        \hline
-        -- &&&&&&&&&&&&&&& \small Spiel {} \\
-        \hline
-        &&&&&&&&&&&&&&& \small {} \\
-        \hline
-        \multicolumn{{8}}{{|c|}}{{\multirow{{2}}{{*}}{{\shortstack[c]{{\small \\[0.75cm]\rule{{0.8\dimexpr0.475\textwidth}}{{0.4pt}}\\\footnotesize Unterschrift {}}}}}}} & \multicolumn{{8}}{{c|}}{{\multirow{{2}}{{*}}{{\shortstack[c]{{\small \\[0.75cm]\rule{{0.8\dimexpr0.525\textwidth}}{{0.4pt}}\\\footnotesize Unterschrift {}}}}}}} \\
-        \multicolumn{{8}}{{|c|}}{{}} & \multicolumn{{8}}{{c|}}{{}} \\
-        \hline
-    \end{{tabularx}}
-",
-                    _match.team_a + 1,
-                    team_a_name,
-                    _match.team_b + 1,
-                    team_b_name,
-                    _match.team_a + 1,
-                    _match.lane + 1,
-                    _match.batch + 1,
-                    group_name,
-                    team_b_name,
-                    team_a_name,
-                    )
-                }).collect::<Vec<String>>().join(r"    \\[2cm]
-    ")
-            })
-            .collect::<Vec<String>>()
-            .join(r"    \\[2cm]
-    ");
-
-        format!(
-            r"
-\documentclass{{article}}
-\usepackage[a4paper]{{geometry}}
-\usepackage{{fontspec}}
-\usepackage{{tabularx}}
-\usepackage{{diagbox}}
-\usepackage{{multirow}}
-
-\geometry{{
-    a4paper,
-    total={{200mm,290mm}},
-    left=0mm,
-    top=2mm,
-}}
-\setmainfont{{FreeSans}}
-\pagenumbering{{gobble}}
-
-\begin{{document}}
-    {}
-\end{{document}}
-",
-            matchplans
-        )
+            .any(|_match| !results.iter().any(|result| result.lane == _match.lane))
+        {
+            return Err(String::from("Please enter results for each match played."));
+        }
+        Ok(())
     }
 
-    fn get_as_json_string(&self) -> String {
-        let teams = if let Some(teams_vec) = self.teams.as_ref() {
-            teams_vec
-                .iter()
-                .map(|group| {
-                    format!(
-                        r"[
-            {}
-        ]",
-                        group
-                            .iter()
-                            .map(|team| {
-                                format!(
-                                    r#"{{
-                "name": "{}",
-                "region": "{}",
-                "player_names": [
-                    {}
-                ]
-            }}"#,
-                                    &team.name,
-                                    &team.region,
-                                    team.player_names
-                                        .iter()
-                                        .map(|player_name_opt| {
-                                            if let Some(player_name) = player_name_opt {
-                                                format!("\"{player_name}\"")
-                                            } else {
-                                                String::from("null")
-                                            }
-                                        })
-                                        .collect::<Vec<String>>()
-                                        .join(",\n                    ")
-                                )
-                            })
-                            .collect::<Vec<String>>()
-                            .join(",\n            ")
-                    )
-                })
-                .collect::<Vec<String>>()
-                .join(",\n        ")
-        } else {
-            String::from("")
+    // submits the results of the current batch of a group, the returned event has to be written to the journal
+    // the batch is checked, so that a repeated submission can not be applied to the next batch
+    pub fn submit_batch_results(
+        &mut self,
+        group_idx: usize,
+        batch: u32,
+        results: Vec<LaneResult>,
+    ) -> Result<JournalEvent, String> {
+        self.check_batch_results(group_idx, &results)?;
+        if self.current_batch[group_idx] != batch {
+            return Err(format!(
+                "Batch {} is not the current batch of the group!",
+                batch + 1
+            ));
+        }
+        let event = JournalEvent::BatchSubmitted {
+            group_idx,
+            batch,
+            results,
         };
+        event.apply(self)?;
+        Ok(event)
+    }
 
-        let group_names = if let Some(group_names) = self.group_names.as_ref() {
-            group_names
-                .iter()
-                .map(|group_name| format!("\"{group_name}\""))
-                .collect::<Vec<String>>()
-                .join(",\n        ")
-        } else {
-            String::from("")
+    fn get_as_json_string(&self) -> Result<String, String> {
+        let save_file = SaveFile {
+            version: CURRENT_FORMAT_VERSION,
+            data: self,
         };
 
-        let matches = self
-            .matches
-            .iter()
-            .map(|group| {
-                format!(
-                    "[
-            {}
-        ]",
-                    group
-                        .iter()
-                        .map(|_match| {
-                            let points = if let Some(points) = _match.points {
-                                format!(
-                                    r#"[
-                    {},
-                    {}
-                ]"#,
-                                    points[0], points[1]
-                                )
-                            } else {
-                                String::from("null")
-                            };
-                            format!(
-                                r#"{{
-                "team_a": {},
-                "team_b": {},
-                "points": {points},
-                "result": "{}",
-                "batch": {},
-                "lane": {}
-            }}"#,
-                                _match.team_a,
-                                _match.team_b,
-                                _match.result,
-                                _match.batch,
-                                _match.lane,
-                            )
-                        })
-                        .collect::<Vec<String>>()
-                        .join(",\n            ")
-                )
-            })
-            .collect::<Vec<String>>()
-            .join(",\n        ");
-
-        let current_batch = self
-            .current_batch
-            .iter()
-            .map(|batch| batch.to_string())
-            .collect::<Vec<String>>()
-            .join(",\n        ");
-
-        format!(
-            r#"{{
-    "name": "{}",
-    "date_string": "{}",
-    "place": "{}",
-    "executor": "{}",
-    "organizer": "{}",
-    "referee": "{}",
-    "competition_manager": "{}",
-    "clerk": "{}",
-    "additional_text": "{}",
-    "count_teams": {},
-    "team_distribution": [
-        {},
-        {}
-    ],
-    "teams": [
-        {teams}
-    ],
-    "group_names": [
-        {group_names}
-    ],
-    "matches": [
-        {matches}
-    ],
-    "current_batch": [
-        {current_batch}
-    ],
-    "with_break": {}
-}}
-"#,
-            self.name,
-            self.date_string,
-            self.place,
-            self.executor,
-            self.organizer,
-            self.referee,
-            self.competition_manager,
-            self.clerk,
-            self.additional_text.replace("\n", r"\n"),
-            self.count_teams,
-            self.team_distribution[0],
-            self.team_distribution[1],
-            self.with_break,
-        )
+        match serde_json::to_string_pretty(&save_file) {
+            Ok(json_string) => Ok(json_string),
+            Err(_) => Err(String::from(
+                "Serialization of the competition data failed!",
+            )),
+        }
     }
 }
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Team {
     pub name: String,
     pub region: String,
     pub player_names: [Option<String>; 6], // maximal 6 possible players per team
+    #[serde(default)]
+    pub license_number: String, // the license number of the team at the federation, empty if unknown
 }
 
 pub struct InterimResultEntry {
@@ -1091,7 +897,47 @@ pub struct InterimResultEntry {
     pub quotient: f32,
 }
 
-#[derive(Clone, Deserialize)]
+pub struct RegionResultEntry {
+    pub region: String,
+    pub count_teams: u32,
+    pub points: i32, // the points used for the ranking, depend on the chosen RegionScoring
+    pub match_points: [i32; 2],
+    pub stock_points: [i32; 2],
+    pub quotient: f32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RegionScoring {
+    PlacementPoints, // each team gets points for its placement in the group, the last team gets 1 point
+    MatchPoints,     // the match points of all teams of a region are summed up
+}
+
+impl RegionScoring {
+    pub fn all() -> [RegionScoring; 2] {
+        [RegionScoring::PlacementPoints, RegionScoring::MatchPoints]
+    }
+}
+
+impl Default for RegionScoring {
+    fn default() -> Self {
+        RegionScoring::PlacementPoints
+    }
+}
+
+impl Display for RegionScoring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                RegionScoring::PlacementPoints => "Placement points",
+                RegionScoring::MatchPoints => "Match points",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Match {
     // the both opponents
     pub team_a: usize,
@@ -1102,7 +948,19 @@ pub struct Match {
     pub lane: u32,                // the number of the lane the match is played on, e.g. "Bahn 2"
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+impl Match {
+    // sets the points of a played match and derives the result from them
+    pub fn set_points(&mut self, points: [i32; 2]) {
+        self.points = Some(points);
+        self.result = match points[0].cmp(&points[1]) {
+            Ordering::Less => MatchResult::WinnerB,
+            Ordering::Equal => MatchResult::Draw,
+            Ordering::Greater => MatchResult::WinnerA,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MatchResult {
     WinnerA,
     Draw,
@@ -1127,6 +985,24 @@ impl Display for MatchResult {
     }
 }
 
+// the id of a match printed on the lane cards and used by the result entry, "group-batch-lane" starting at 0
+pub fn match_id(group_idx: usize, batch: u32, lane: u32) -> String {
+    format!("{group_idx}-{batch}-{lane}")
+}
+
+// parses an id created by match_id, surrounding whitespace of a scanner is ignored
+pub fn parse_match_id(id: &str) -> Option<(usize, u32, u32)> {
+    let ids: Vec<u32> = id
+        .trim()
+        .split('-')
+        .map(|id| id.parse().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    match ids.as_slice() {
+        [group_idx, batch, lane] => Some((*group_idx as usize, *batch, *lane)),
+        _ => None,
+    }
+}
+
 pub fn calc_group_possibilities(count_teams: u32) -> Vec<[u32; 2]> {
     if count_teams == 0 {
         Vec::new()
@@ -1141,3 +1017,87 @@ pub fn calc_group_possibilities(count_teams: u32) -> Vec<[u32; 2]> {
         possibilities
     }
 }
+
+// a group of two teams with their first match not played yet, shared by the tests of the exports and the servers
+#[cfg(test)]
+pub(crate) fn two_team_competition() -> CompetitionData {
+    let mut data = CompetitionData::empty();
+    data.count_teams = 2;
+    data.team_distribution = [1, 2];
+    data.group_names = Some(vec![String::from("Gruppe A")]);
+    data.teams = Some(vec![vec![
+        Team {
+            name: String::from("SV Musterstadt"),
+            region: String::from("Kreis 1"),
+            player_names: Default::default(),
+            license_number: String::new(),
+        },
+        Team {
+            name: String::from("TSV Beispiel"),
+            region: String::from("Kreis 2"),
+            player_names: Default::default(),
+            license_number: String::new(),
+        },
+    ]]);
+    data.current_batch = vec![0];
+    data.matches = vec![vec![Match {
+        team_a: 0,
+        team_b: 1,
+        points: None,
+        result: MatchResult::NotPlayed,
+        batch: 0,
+        lane: 0,
+    }]];
+    data
+}
+
+#[cfg(test)]
+#[test]
+fn test_region_result() {
+    let mut data = two_team_competition();
+    data.matches[0][0].set_points([7, 3]);
+    // a second group with a region typed with a trailing space and a team without region
+    data.teams.as_mut().unwrap().push(vec![
+        Team {
+            name: String::from("FC Gast"),
+            region: String::from("Kreis 1 "),
+            player_names: Default::default(),
+            license_number: String::new(),
+        },
+        Team {
+            name: String::from("SC Ohne Kreis"),
+            region: String::from(" "),
+            player_names: Default::default(),
+            license_number: String::new(),
+        },
+    ]);
+    data.matches.push(data.matches[0].clone());
+    data.matches[1][0].set_points([2, 8]);
+    data.current_batch.push(0);
+
+    let table = data.calc_region_result();
+    assert_eq!(table.len(), 2);
+    assert_eq!(table[0].region, "Kreis 1");
+    assert_eq!(table[0].count_teams, 2);
+    assert_eq!(table[0].points, 3);
+    assert_eq!(table[0].stock_points, [9, 11]);
+    assert_eq!(table[1].region, "Kreis 2");
+    assert_eq!(table[1].points, 1);
+
+    data.region_scoring = RegionScoring::MatchPoints;
+    let table = data.calc_region_result();
+    assert_eq!(table[0].region, "Kreis 1");
+    assert_eq!(table[0].points, 2);
+    assert_eq!(table[0].match_points, [2, 2]);
+    assert_eq!(table[1].points, 0);
+}
+
+#[cfg(test)]
+#[test]
+fn test_match_id() {
+    assert_eq!(match_id(1, 4, 2), "1-4-2");
+    // a keyboard-wedge scanner may add whitespace or a line break
+    assert_eq!(parse_match_id(" 1-4-2\r\n"), Some((1, 4, 2)));
+    assert_eq!(parse_match_id("1-4"), None);
+    assert_eq!(parse_match_id("1-x-2"), None);
+}
@@ -1,46 +1,294 @@
 use std::{
-    fs,
-    path::PathBuf,
-    sync::mpsc::{self, TryRecvError},
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
 };
 
-use chrono::{Duration, Local};
+use chrono::Local;
 use native_dialog::MessageType;
-use timer::Timer;
+use serde_json::Value;
 
 use crate::{ProgramStage, ProgramState};
 
 #[cfg(test)]
-use super::Team;
+use super::{RegionScoring, Team};
 
-use super::CompetitionData;
+use super::{
+    archive::{build_archive, is_archive, read_archive_json, ARCHIVE_EXTENSION},
+    migration::migrate,
+    CompetitionData,
+};
+
+pub fn save_to_file(
+    file_path: PathBuf,
+    data: &CompetitionData,
+    backup_count: u32,
+) -> Result<(), String> {
+    let json = data.get_as_json_string()?;
+
+    // archives bundle the competition data with the files stored next to them
+    let content = if is_archive(&file_path) {
+        build_archive(&file_path, &json)?
+    } else if is_database(&file_path) {
+        build_database(&file_path, data)?
+    } else {
+        json.into_bytes()
+    };
 
-pub fn save_to_file(file_path: PathBuf, data: &CompetitionData) -> Result<(), String> {
-    let json = data.get_as_json_string();
     if let Some(parent) = file_path.parent() {
         match fs::create_dir_all(parent) {
             Ok(_) => (),
             Err(_) => return Err(String::from("Creation of parents dir failed!")),
         };
     }
-    match fs::write(file_path, json) {
+
+    // keep the previous version of the file, a failed backup must not prevent saving the current data
+    if backup_count > 0 && file_path.is_file() {
+        if let Err(msg) = create_backup(&file_path, backup_count) {
+            eprintln!("[save_to_file]: {msg}");
+        }
+    }
+
+    write_atomically(&file_path, &content)
+}
+
+// writes the content to a temporary file next to the target and renames it afterwards,
+// so that the target either contains the old or the new content, but never a truncated one
+fn write_atomically(file_path: &Path, content: &[u8]) -> Result<(), String> {
+    let file_name = match file_path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().to_string(),
+        None => return Err(String::from("Path has no file name!")),
+    };
+    let tmp_path = file_path.with_file_name(format!(".{file_name}.tmp"));
+
+    let write_res = File::create(&tmp_path)
+        .and_then(|mut file| file.write_all(content).and_then(|_| file.sync_all()));
+
+    if write_res.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(String::from("Write to file failed!"));
+    }
+
+    match fs::rename(&tmp_path, file_path) {
         Ok(_) => Ok(()),
-        Err(_) => Err(String::from("Write to file failed!")),
+        Err(_) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(String::from("Replacing the file failed!"))
+        }
+    }
+}
+
+// the folder next to the competition file where the backups are stored
+fn backup_dir(file_path: &Path) -> PathBuf {
+    match file_path.parent() {
+        Some(parent) => parent.join("backups"),
+        None => PathBuf::from("backups"),
+    }
+}
+
+// the prefix of all backup file names of a competition file, followed by a timestamp
+fn backup_prefix(file_path: &Path) -> String {
+    match file_path.file_stem() {
+        Some(stem) => format!("{}-", stem.to_string_lossy()),
+        None => String::from("backup-"),
     }
 }
 
+fn create_backup(file_path: &Path, backup_count: u32) -> Result<(), String> {
+    let backup_dir = backup_dir(file_path);
+    if fs::create_dir_all(&backup_dir).is_err() {
+        return Err(String::from("Creation of backup dir failed!"));
+    }
+
+    // keep the extension, so that backups of archives are archives again
+    let extension = match file_path.extension() {
+        Some(extension) => extension.to_string_lossy().to_string(),
+        None => String::from("json"),
+    };
+    let backup_path = backup_dir.join(format!(
+        "{}{}.{extension}",
+        backup_prefix(file_path),
+        Local::now().format("%Y%m%d-%H%M%S%3f")
+    ));
+    if fs::copy(file_path, &backup_path).is_err() {
+        return Err(format!(
+            "Creation of backup {} failed!",
+            backup_path.display()
+        ));
+    }
+
+    // remove the oldest backups, if there are more than backup_count
+    let backups = list_backups(file_path);
+    if backups.len() > backup_count as usize {
+        backups[backup_count as usize..]
+            .iter()
+            .for_each(|old_backup| {
+                if fs::remove_file(old_backup).is_err() {
+                    eprintln!(
+                        "[create_backup]: Could not remove old backup {}",
+                        old_backup.display()
+                    );
+                }
+            });
+    }
+    Ok(())
+}
+
+// returns all backups of the competition file, the newest backup first
+pub fn list_backups(file_path: &Path) -> Vec<PathBuf> {
+    let prefix = backup_prefix(file_path);
+    let mut backups: Vec<PathBuf> = match fs::read_dir(backup_dir(file_path)) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path.extension().map_or(false, |ext| {
+                        ext == "json" || ext == ARCHIVE_EXTENSION || ext == "sqlite"
+                    })
+                    && path.file_stem().map_or(false, |stem| {
+                        // the stem consists of the prefix and the timestamp, e.g. "prefix-20220101-120000000"
+                        match stem.to_string_lossy().strip_prefix(&prefix) {
+                            Some(timestamp) => {
+                                timestamp.len() == 18
+                                    && timestamp.chars().all(|c| c.is_ascii_digit() || c == '-')
+                            }
+                            None => false,
+                        }
+                    })
+            })
+            .collect(),
+        Err(_) => vec![],
+    };
+
+    // the timestamps in the file names are sortable
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+// SQLite databases are only available if ISRAT is built with the "sqlite" feature
+pub fn is_database(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "sqlite")
+}
+
+#[cfg(feature = "sqlite")]
+fn build_database(file_path: &Path, data: &CompetitionData) -> Result<Vec<u8>, String> {
+    super::sqlite::build_database(file_path, data)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn build_database(_file_path: &Path, _data: &CompetitionData) -> Result<Vec<u8>, String> {
+    Err(String::from(
+        "This version of ISRAT was built without SQLite support!",
+    ))
+}
+
+#[cfg(feature = "sqlite")]
+fn read_from_database(path: &Path) -> Result<CompetitionData, String> {
+    super::sqlite::read_from_database(path)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn read_from_database(_path: &Path) -> Result<CompetitionData, String> {
+    Err(String::from(
+        "This version of ISRAT was built without SQLite support!",
+    ))
+}
+
 pub fn read_from_file(path: PathBuf) -> Result<CompetitionData, String> {
-    let json_string = &fs::read_to_string(&path);
-    let json_string = match json_string {
-        Ok(bytes) => bytes,
-        Err(_) => return Err(format!("Error whilst reading file: {}", path.display())),
+    if is_database(&path) {
+        return read_from_database(&path);
+    }
+
+    let json_string = &if is_archive(&path) {
+        read_archive_json(&path)?
+    } else {
+        match fs::read_to_string(&path) {
+            Ok(json_string) => json_string,
+            Err(_) => return Err(format!("Error whilst reading file: {}", path.display())),
+        }
     };
 
     dbg!("{}", json_string);
 
-    match serde_json::from_str(json_string) {
+    // files of the old hand written format may contain unescaped strings, try to repair them
+    let value = match serde_json::from_str(json_string) {
+        Ok(value) => value,
+        Err(_) => match serde_json::from_str(&repair_legacy_json(json_string)) {
+            Ok(value) => value,
+            Err(_) => return Err(String::from("JSON was not well-formatted")),
+        },
+    };
+
+    read_from_json_value(value)
+}
+
+// migrates the json representation of a save file to the current format version and extracts the competition data
+pub fn read_from_json_value(mut value: Value) -> Result<CompetitionData, String> {
+    migrate(&mut value)?;
+
+    match serde_json::from_value(value) {
         Ok(competition_data) => Ok(competition_data),
-        Err(_) => Err(String::from("JSON was not well-formatted")),
+        Err(err) => Err(format!(
+            "The file does not contain valid competition data: {err}"
+        )),
+    }
+}
+
+// Escapes the strings of files written by the old hand written JSON writer, which only escaped the newlines of the additional text.
+// This writer put every string value on its own line, so a quote only closes a string if it is followed by
+// the end of the line (optionally after a comma) or by a colon in case of a key.
+fn repair_legacy_json(json_string: &str) -> String {
+    let chars: Vec<char> = json_string.chars().collect();
+    let mut repaired = String::with_capacity(json_string.len());
+    let mut in_string = false;
+    let mut string_start = 0; // the index in the repaired string where the content of the current string starts
+
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        if !in_string {
+            repaired.push(c);
+            if c == '"' {
+                in_string = true;
+                string_start = repaired.len();
+            }
+        } else {
+            // only the keys, which consist of lowercase letters and underscores, are followed by a colon
+            let is_key = repaired[string_start..]
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c == '_');
+            match c {
+                '"' if is_legacy_string_end(&chars[idx + 1..], is_key) => {
+                    in_string = false;
+                    repaired.push(c);
+                }
+                '"' => repaired.push_str("\\\""),
+                // the old writer only produced "\n" as escape sequence, every other backslash belongs to the text
+                '\\' if chars.get(idx + 1) == Some(&'n') => {
+                    repaired.push_str("\\n");
+                    idx += 1;
+                }
+                '\\' => repaired.push_str("\\\\"),
+                '\n' => repaired.push_str("\\n"),
+                '\r' => repaired.push_str("\\r"),
+                '\t' => repaired.push_str("\\t"),
+                c if c.is_control() => repaired.push_str(&format!("\\u{:04x}", c as u32)),
+                c => repaired.push(c),
+            }
+        }
+        idx += 1;
+    }
+    repaired
+}
+
+fn is_legacy_string_end(following: &[char], is_key: bool) -> bool {
+    let mut rest = following.iter().skip_while(|c| **c == ' ' || **c == '\r');
+    match rest.next() {
+        None | Some('\n') => true,
+        Some(':') => is_key,
+        Some(',') => matches!(rest.find(|c| **c != ' ' && **c != '\r'), None | Some('\n')),
+        _ => false,
     }
 }
 
@@ -74,6 +322,7 @@ fn test_read_write() {
                         //Some(String::from("Mustername A.5")),
                         //Some(String::from("Mustername A.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam B"),
@@ -86,6 +335,7 @@ fn test_read_write() {
                         Some(String::from("Mustername B.5")),
                         Some(String::from("Mustername B.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam C"),
@@ -98,6 +348,7 @@ fn test_read_write() {
                         Some(String::from("Mustername C.5")),
                         Some(String::from("Mustername C.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam D"),
@@ -110,6 +361,7 @@ fn test_read_write() {
                         Some(String::from("Mustername D.5")),
                         Some(String::from("Mustername D.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam E"),
@@ -122,6 +374,7 @@ fn test_read_write() {
                         Some(String::from("Mustername E.5")),
                         Some(String::from("Mustername E.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam F"),
@@ -134,6 +387,7 @@ fn test_read_write() {
                         Some(String::from("Mustername F.5")),
                         Some(String::from("Mustername F.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam G"),
@@ -146,6 +400,7 @@ fn test_read_write() {
                         Some(String::from("Mustername G.5")),
                         Some(String::from("Mustername G.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam H"),
@@ -158,6 +413,7 @@ fn test_read_write() {
                         Some(String::from("Mustername H.5")),
                         Some(String::from("Mustername H.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam I"),
@@ -170,6 +426,7 @@ fn test_read_write() {
                         Some(String::from("Mustername I.5")),
                         Some(String::from("Mustername I.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam J"),
@@ -182,6 +439,7 @@ fn test_read_write() {
                         Some(String::from("Mustername J.5")),
                         Some(String::from("Mustername J.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 /*Team {
                     name: String::from("Musterteam K"),
@@ -208,6 +466,7 @@ fn test_read_write() {
                         Some(String::from("Mustername N.5")),
                         Some(String::from("Mustername N.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam O"),
@@ -220,6 +479,7 @@ fn test_read_write() {
                         Some(String::from("Mustername O.5")),
                         Some(String::from("Mustername O.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam P"),
@@ -232,6 +492,7 @@ fn test_read_write() {
                         Some(String::from("Mustername P.5")),
                         Some(String::from("Mustername P.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam Q"),
@@ -244,6 +505,7 @@ fn test_read_write() {
                         Some(String::from("Mustername Q.5")),
                         Some(String::from("Mustername Q.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam R"),
@@ -256,6 +518,7 @@ fn test_read_write() {
                         Some(String::from("Mustername R.5")),
                         Some(String::from("Mustername R.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam S"),
@@ -268,6 +531,7 @@ fn test_read_write() {
                         Some(String::from("Mustername S.5")),
                         Some(String::from("Mustername S.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam T"),
@@ -280,6 +544,7 @@ fn test_read_write() {
                         Some(String::from("Mustername T.5")),
                         Some(String::from("Mustername T.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam U"),
@@ -292,6 +557,7 @@ fn test_read_write() {
                         Some(String::from("Mustername U.5")),
                         Some(String::from("Mustername U.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam V"),
@@ -304,6 +570,7 @@ fn test_read_write() {
                         Some(String::from("Mustername V.5")),
                         Some(String::from("Mustername V.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam W"),
@@ -316,6 +583,7 @@ fn test_read_write() {
                         Some(String::from("Mustername W.5")),
                         Some(String::from("Mustername W.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 /*Team {
                     name: String::from("Musterteam X"),
@@ -338,11 +606,14 @@ fn test_read_write() {
         matches: vec![],
         current_batch: vec![1, 0],
         with_break: true,
+        region_scoring: RegionScoring::PlacementPoints,
+        start_time: String::from("09:00"),
+        batch_duration_minutes: 30,
     };
 
     data.generate_matches();
 
-    let export_result = save_to_file(PathBuf::from("./tmp/documents/save.json"), &data);
+    let export_result = save_to_file(PathBuf::from("./tmp/documents/save.json"), &data, 0);
 
     assert!(export_result.is_ok());
 
@@ -404,6 +675,117 @@ fn test_read_write() {
 
     debug_assert_eq!(data.current_batch, read_data.current_batch);
     debug_assert_eq!(data.with_break, read_data.with_break);
+    debug_assert_eq!(data.region_scoring, read_data.region_scoring);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_write_special_characters() {
+    let mut data = CompetitionData::empty();
+    data.name = String::from(r#"Club "Eis" 1"#);
+    data.place = String::from(r"C:\Users\new\tmp");
+    data.additional_text =
+        String::from("Zeile 1\nZeile 2\r\n\tEingerückt \u{0}\u{1b} 🥌 こんにちは");
+    data.count_teams = 2;
+    data.team_distribution = [1, 2];
+    data.teams = Some(vec![vec![
+        Team {
+            name: String::from(r#"Team "A" \"#),
+            region: String::from("Kreis\t1"),
+            player_names: [
+                Some(String::from(r#"Max "Stock" Mustermann"#)),
+                Some(String::from("Ünïcödé ß €")),
+                None,
+                None,
+                None,
+                None,
+            ],
+            license_number: String::from(""),
+        },
+        Team {
+            name: String::from("Team\\nB"),
+            region: String::from("}],\""),
+            player_names: [None, None, None, None, None, None],
+            license_number: String::from(""),
+        },
+    ]]);
+    data.group_names = Some(vec![String::from(r#"Gruppe "ROT", 1"#)]);
+    data.generate_matches();
+
+    let path = PathBuf::from("./tmp/documents/save_special_characters.json");
+    assert!(save_to_file(path.clone(), &data, 0).is_ok());
+
+    let read_data = read_from_file(path);
+    assert!(read_data.is_ok());
+    let read_data = read_data.unwrap();
+
+    assert_eq!(data.name, read_data.name);
+    assert_eq!(data.place, read_data.place);
+    assert_eq!(data.additional_text, read_data.additional_text);
+    assert_eq!(data.group_names, read_data.group_names);
+    data.teams.as_ref().unwrap()[0]
+        .iter()
+        .zip(read_data.teams.as_ref().unwrap()[0].iter())
+        .for_each(|(data_team, read_team)| {
+            assert_eq!(data_team.name, read_team.name);
+            assert_eq!(data_team.region, read_team.region);
+            assert_eq!(data_team.player_names, read_team.player_names);
+        });
+}
+
+#[cfg(test)]
+#[test]
+fn test_repair_legacy_json() {
+    // excerpt of a file written by the old hand written JSON writer
+    let legacy_json = "{
+    \"name\": \"Club \"Eis\" 1\",
+    \"place\": \"C:\\Users\\tmp\",
+    \"executor\": \"Team \"A\": B\",
+    \"additional_text\": \"Zeile 1\\nZeile 2\",
+    \"group_names\": [
+        \"Gruppe \"ROT\"\",
+        \"Gruppe BLAU\"
+    ],
+    \"with_break\": true
+}
+";
+    assert!(serde_json::from_str::<Value>(legacy_json).is_err());
+
+    let value = serde_json::from_str::<Value>(&repair_legacy_json(legacy_json));
+    assert!(value.is_ok());
+    let value = value.unwrap();
+
+    assert_eq!(value["name"], Value::from(r#"Club "Eis" 1"#));
+    assert_eq!(value["place"], Value::from(r"C:\Users\tmp"));
+    assert_eq!(value["executor"], Value::from(r#"Team "A": B"#));
+    assert_eq!(value["additional_text"], Value::from("Zeile 1\nZeile 2"));
+    assert_eq!(value["group_names"][0], Value::from(r#"Gruppe "ROT""#));
+    assert_eq!(value["group_names"][1], Value::from("Gruppe BLAU"));
+    assert_eq!(value["with_break"], Value::from(true));
+}
+
+#[cfg(test)]
+#[test]
+fn test_backup_rotation() {
+    let mut data = CompetitionData::empty();
+    data.name = String::from("Backupmeisterschaft");
+
+    let path = PathBuf::from("./tmp/documents/backups_test/save.json");
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+
+    for _ in 0..5 {
+        assert!(save_to_file(path.clone(), &data, 2).is_ok());
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    // the first save has nothing to backup, afterwards only the newest backups are kept
+    assert!(path.is_file());
+    assert_eq!(list_backups(&path).len(), 2);
+    assert!(!path.with_file_name(".save.json.tmp").exists());
+    assert_eq!(
+        read_from_file(list_backups(&path)[0].clone()).unwrap().name,
+        data.name
+    );
 }
 
 pub fn check_read_write_threads_messages(program_state: &mut ProgramState) {
@@ -422,7 +804,10 @@ pub fn check_read_write_threads_messages(program_state: &mut ProgramState) {
         // check for error and else save the data to the given path
         match path_res {
             Ok(path_opt) => match path_opt {
-                Some(path) => match program_state.competition.handle_save_file(path) {
+                Some(path) => match program_state
+                    .competition
+                    .handle_save_file(path, program_state.settings.backup_count)
+                {
                     Ok(_) => (),
                     Err(msg) => show_error_message("save_as_button", msg),
                 },
@@ -469,78 +854,3 @@ fn show_error_message(function: &str, msg: String) {
         Err(_) => eprintln!("[{function}]: Could not open message dialog! Error message: {msg}"),
     }
 }
-
-// FIXME: Crashes because channel is closed. Either find the bug or remove the library and write own implementation
-pub fn spawn_autosave_timer(interval: Duration, program_state: &mut ProgramState) {
-    let (tx, rx) = mpsc::channel();
-    let timer = Timer::new();
-    let guard = timer.schedule_repeating(interval, move || {
-        // TODO: Log it!
-        println!("Autosave Timer: Sending save signal message!");
-        match tx.send(()) {
-            Ok(()) => (),
-            Err(_) => panic!("Autosave Timer: Cannot send message!"),
-        }
-    });
-
-    program_state.threads.autosave_guard = Some(guard);
-    program_state.threads.autosave_channel = Some(rx);
-    program_state.threads.timer = Some(timer);
-}
-
-pub fn check_autosave_thread_messages(program_state: &mut ProgramState) {
-    let rx = match program_state.threads.autosave_channel.as_ref() {
-        Some(rx) => rx,
-        None => return,
-    };
-
-    match rx.try_recv() {
-        Ok(()) => (),
-        Err(TryRecvError::Empty) => return,
-        Err(TryRecvError::Disconnected) => panic!("Autosave Timer: Sender disconnected!"),
-    }
-
-    // use set file path or the default path in tmp
-    let path = match program_state.competition.absolute_file_path.as_ref() {
-        Some(path) => path.to_path_buf(),
-        None => match default_path_for_autosave(program_state) {
-            Ok(default_path) => default_path,
-            Err(_) => return, // TODO: Log it!,
-        },
-    };
-
-    // TODO: Log it
-    println!(
-        "Received autosave signal, starting save to {} at {}",
-        path.display().to_string(),
-        Local::now().format("%d.%m.%Y %H:%M:%S")
-    );
-
-    match program_state.competition.handle_save_file(path) {
-        Ok(_) => println!(
-            "Finished autosave at {}",
-            Local::now().format("%d.%m.%Y %H:%M:%S")
-        ),
-        Err(_) => (), //TODO: LOG it,
-    }
-}
-
-// TODO: Set this to more useful default path than tmp dir
-fn default_path_for_autosave(program_state: &ProgramState) -> Result<PathBuf, String> {
-    let default_folder = std::env::temp_dir().join("israt").join("autosaves");
-    let data = match program_state.competition.data.as_ref() {
-        Some(data) => data,
-        None => {
-            return Err(String::from(
-                "There is no competition data, cannot create default autosave file name!",
-            ))
-        }
-    };
-
-    let sanitized_compettion_name = data.name.replace(&['/', '\\', '%', '.', '~'], "");
-    let autosave_file_name = format!(
-        "{sanitized_compettion_name}-{}.json",
-        Local::now().format("%Y%m%d-%H%M")
-    );
-    return Ok(default_folder.join(autosave_file_name));
-}
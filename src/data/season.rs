@@ -0,0 +1,512 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+};
+
+use chrono::Local;
+use minijinja::context;
+use native_dialog::{FileDialog, MessageType};
+use serde::{Deserialize, Serialize};
+
+use crate::ProgramState;
+
+use super::{
+    latex_export::escape_latex,
+    read_write::{read_from_file, read_from_json_value},
+    templates::{render_template, SEASON_TABLE_TEMPLATE},
+    CompetitionData, MatchResult,
+};
+
+// A season combines several competitions (matchdays) played by the same teams to one cumulative table.
+#[derive(Serialize, Deserialize)]
+pub struct SeasonData {
+    pub name: String,
+    #[serde(default)]
+    pub carry_over: SeasonCarryOver,
+    pub matchdays: Vec<Matchday>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub enum Matchday {
+    Reference(PathBuf), // path to an ISRAT competition file, relative paths are relative to the season file
+    Embedded(serde_json::Value), // the save file content of the competition stored in the season file
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SeasonCarryOver {
+    MatchPoints,     // the match and stock points of all matchdays are summed up
+    PlacementPoints, // each team gets points for its placement on a matchday, the last team of a group gets 1 point
+}
+
+impl SeasonCarryOver {
+    pub fn all() -> [SeasonCarryOver; 2] {
+        [
+            SeasonCarryOver::MatchPoints,
+            SeasonCarryOver::PlacementPoints,
+        ]
+    }
+}
+
+impl Default for SeasonCarryOver {
+    fn default() -> Self {
+        SeasonCarryOver::MatchPoints
+    }
+}
+
+impl Display for SeasonCarryOver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SeasonCarryOver::MatchPoints => "Match points",
+                SeasonCarryOver::PlacementPoints => "Placement points",
+            }
+        )
+    }
+}
+
+pub struct SeasonResultEntry {
+    pub team_name: String,
+    pub region: String,
+    pub matchday_points: Vec<Option<i32>>, // the points for each matchday, None if the team did not participate
+    pub points: i32,
+    pub match_points: [i32; 2],
+    pub stock_points: [i32; 2],
+    pub quotient: f32,
+}
+
+impl SeasonData {
+    pub fn new(name: String) -> Self {
+        SeasonData {
+            name,
+            carry_over: SeasonCarryOver::default(),
+            matchdays: vec![],
+        }
+    }
+
+    pub fn read_from_file(path: &Path) -> Result<SeasonData, String> {
+        let json_string = match fs::read_to_string(path) {
+            Ok(json_string) => json_string,
+            Err(_) => return Err(format!("Error whilst reading file: {}", path.display())),
+        };
+
+        match serde_json::from_str(&json_string) {
+            Ok(season_data) => Ok(season_data),
+            Err(_) => Err(String::from("Season file was not well-formatted")),
+        }
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<(), String> {
+        match fs::write(path, self.get_as_json_string()?) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(String::from("Write to season file failed!")),
+        }
+    }
+
+    fn get_as_json_string(&self) -> Result<String, String> {
+        match serde_json::to_string_pretty(self) {
+            Ok(json_string) => Ok(json_string),
+            Err(_) => Err(String::from("Season could not be serialized!")),
+        }
+    }
+
+    // adds a reference to the competition file, if it is not yet part of the season
+    pub fn add_competition_reference(&mut self, competition_path: PathBuf) {
+        let already_contained = self.matchdays.iter().any(|matchday| match matchday {
+            Matchday::Reference(path) => *path == competition_path,
+            Matchday::Embedded(_) => false,
+        });
+
+        if !already_contained {
+            self.matchdays.push(Matchday::Reference(competition_path));
+        }
+    }
+
+    // loads the competition data of all matchdays in the order they were added to the season
+    pub fn load_matchdays(&self, season_path: &Path) -> Result<Vec<CompetitionData>, String> {
+        let season_dir = season_path.parent().unwrap_or(Path::new(""));
+        self.matchdays
+            .iter()
+            .map(|matchday| match matchday {
+                Matchday::Reference(path) => {
+                    let path = if path.is_absolute() {
+                        path.to_path_buf()
+                    } else {
+                        season_dir.join(path)
+                    };
+                    read_from_file(path.clone()).map_err(|msg| {
+                        format!("Matchday {} could not be loaded: {msg}", path.display())
+                    })
+                }
+                // embedded competitions are migrated like regular save files
+                Matchday::Embedded(value) => read_from_json_value(value.clone()),
+            })
+            .collect()
+    }
+
+    pub fn calc_season_result(&self, matchdays: &Vec<CompetitionData>) -> Vec<SeasonResultEntry> {
+        let mut table: Vec<SeasonResultEntry> = vec![];
+
+        matchdays
+            .iter()
+            .enumerate()
+            .for_each(|(matchday_idx, data)| {
+                let teams = match data.teams.as_ref() {
+                    Some(teams) => teams,
+                    None => return,
+                };
+
+                (0..teams.len()).for_each(|group_idx| {
+                    let group_result = data.calc_interim_result_for_group(group_idx);
+                    let group_size = group_result.len() as i32;
+
+                    group_result.iter().enumerate().for_each(|(rank, i_res)| {
+                        let team = &teams[group_idx][i_res.team_idx];
+
+                        // teams are identified by their name over all matchdays
+                        let entry = match table
+                            .iter()
+                            .position(|entry| entry.team_name == team.name.trim())
+                        {
+                            Some(entry_idx) => &mut table[entry_idx],
+                            None => {
+                                table.push(SeasonResultEntry {
+                                    team_name: team.name.trim().to_string(),
+                                    region: team.region.clone(),
+                                    matchday_points: vec![None; matchdays.len()],
+                                    points: 0,
+                                    match_points: [0, 0],
+                                    stock_points: [0, 0],
+                                    quotient: 0.0,
+                                });
+                                table.last_mut().unwrap()
+                            }
+                        };
+
+                        let points = match self.carry_over {
+                            SeasonCarryOver::MatchPoints => i_res.match_points[0],
+                            SeasonCarryOver::PlacementPoints => group_size - rank as i32,
+                        };
+
+                        entry.matchday_points[matchday_idx] = Some(points);
+                        entry.points += points;
+                        entry.match_points[0] += i_res.match_points[0];
+                        entry.match_points[1] += i_res.match_points[1];
+                        entry.stock_points[0] += i_res.stock_points[0];
+                        entry.stock_points[1] += i_res.stock_points[1];
+                    });
+                });
+            });
+
+        // calculate quotient
+        table.iter_mut().for_each(|entry| {
+            entry.quotient = if entry.stock_points[0] == 0 {
+                0.0
+            } else {
+                (entry.stock_points[0] as f32) / (entry.stock_points[1] as f32)
+            };
+        });
+
+        // sort the table, ties are broken by the quotient and the stock point difference like in the group results
+        table.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then(
+                    b.quotient
+                        .partial_cmp(&a.quotient)
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+                .then(
+                    (b.stock_points[0] - b.stock_points[1])
+                        .cmp(&(a.stock_points[0] - a.stock_points[1])),
+                )
+        });
+        table
+    }
+
+    pub fn get_season_result_as_latex(
+        &self,
+        matchdays: &Vec<CompetitionData>,
+    ) -> Result<String, String> {
+        // a matchday is finished if all of its matches were played
+        let count_finished_matchdays = matchdays
+            .iter()
+            .filter(|data| {
+                data.matches.iter().all(|group_matches| {
+                    group_matches
+                        .iter()
+                        .all(|_match| _match.result != MatchResult::NotPlayed)
+                })
+            })
+            .count();
+
+        let standings: Vec<SeasonStandingContext> = self
+            .calc_season_result(matchdays)
+            .into_iter()
+            .enumerate()
+            .map(|(rank, entry)| SeasonStandingContext {
+                place: rank + 1,
+                team_name: escape_latex(&entry.team_name),
+                region: escape_latex(&entry.region),
+                matchday_points: entry.matchday_points,
+                points: entry.points,
+                quotient: format!("{:.3}", entry.quotient),
+                stock_points: entry.stock_points,
+            })
+            .collect();
+
+        render_template(
+            SEASON_TABLE_TEMPLATE,
+            context! {
+                season => context! { name => escape_latex(&self.name) },
+                created_at => Local::now().format("%d.%m.%Y %H:%M").to_string(),
+                count_finished_matchdays => count_finished_matchdays,
+                count_matchdays => matchdays.len(),
+                standings => standings,
+            },
+        )
+    }
+}
+
+// a row of the season table template, the texts are escaped
+#[derive(Serialize)]
+struct SeasonStandingContext {
+    place: usize,
+    team_name: String,
+    region: String,
+    matchday_points: Vec<Option<i32>>,
+    points: i32,
+    quotient: String,
+    stock_points: [i32; 2],
+}
+
+// opens a save dialog to choose the season file the current competition is added to
+pub fn add_to_season_action(program_state: &mut ProgramState) {
+    if program_state.competition.absolute_file_path.is_none() {
+        show_error_message(
+            "add_to_season_action",
+            String::from("Save the competition before adding it to a season."),
+        );
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let absolute_dir_path = program_state.competition.absolute_dir_path.clone();
+
+    // open os save as file dialog in separate thread in order to not stop the GUI rendering
+    thread::Builder::new()
+        .name(String::from("Add to season dialog thread"))
+        .spawn(move || {
+            let mut dialog = FileDialog::new()
+                .add_filter("ISRAT Season Files", &["json"])
+                .set_filename("season.json");
+
+            if let Some(dir_path) = absolute_dir_path.as_ref() {
+                dialog = dialog.set_location(dir_path);
+            }
+
+            let dialog_res = dialog.show_save_single_file();
+
+            // inform main (GUI) Thread about closed dialog
+            tx.send(dialog_res)
+                .expect("Channel is closed, this is not expected");
+        })
+        .expect("This shouldn't happen!");
+
+    program_state.threads.season_add_channels.push(rx);
+}
+
+// opens an open dialog to choose the season file which table is exported
+pub fn export_season_table_action(program_state: &mut ProgramState) {
+    let (tx, rx) = mpsc::channel();
+    let absolute_dir_path = program_state.competition.absolute_dir_path.clone();
+
+    // open os open file dialog in separate thread in order to not stop the GUI rendering
+    thread::Builder::new()
+        .name(String::from("Export season table dialog thread"))
+        .spawn(move || {
+            let mut dialog = FileDialog::new().add_filter("ISRAT Season Files", &["json"]);
+
+            if let Some(dir_path) = absolute_dir_path.as_ref() {
+                dialog = dialog.set_location(dir_path);
+            }
+
+            let dialog_res = dialog.show_open_single_file();
+
+            // inform main (GUI) Thread about closed dialog
+            tx.send(dialog_res)
+                .expect("Channel is closed, this is not expected");
+        })
+        .expect("This shouldn't happen!");
+
+    program_state.threads.season_export_channels.push(rx);
+}
+
+pub fn check_season_threads_messages(program_state: &mut ProgramState) {
+    // check if any of the add to season threads send a new message and remove the corresponding entry iff the thread has finished its work
+    let mut i = 0;
+    while i < program_state.threads.season_add_channels.len() {
+        let path_res = match program_state.threads.season_add_channels[i].try_recv() {
+            Ok(path_res) => path_res,
+            Err(_) => {
+                i += 1;
+                continue;
+            }
+        };
+
+        match path_res {
+            Ok(Some(season_path)) => {
+                let carry_over = program_state.main_menu_bar_state.season_carry_over;
+                match add_competition_to_season_file(program_state, season_path, carry_over) {
+                    Ok(_) => (),
+                    Err(msg) => show_error_message("add_to_season_action", msg),
+                }
+            }
+            Ok(None) => eprintln!("[add_to_season_action]: FileDialog returned None path!"),
+            Err(msg) => show_error_message("add_to_season_action", msg.to_string()),
+        }
+
+        program_state.threads.season_add_channels.remove(i);
+    }
+
+    // check if any of the export season table threads send a new message and remove the corresponding entry iff the thread has finished its work
+    i = 0;
+    while i < program_state.threads.season_export_channels.len() {
+        let path_res = match program_state.threads.season_export_channels[i].try_recv() {
+            Ok(path_res) => path_res,
+            Err(_) => {
+                i += 1;
+                continue;
+            }
+        };
+
+        match path_res {
+            Ok(Some(season_path)) => {
+                let tex_bundle = program_state.settings.tex_bundle();
+                match program_state
+                    .competition
+                    .export_season_table(season_path, tex_bundle)
+                {
+                    Ok(_) => (),
+                    Err(msg) => show_error_message("export_season_table_action", msg),
+                }
+            }
+            Ok(None) => eprintln!("[export_season_table_action]: FileDialog returned None path!"),
+            Err(msg) => show_error_message("export_season_table_action", msg.to_string()),
+        }
+
+        program_state.threads.season_export_channels.remove(i);
+    }
+}
+
+fn add_competition_to_season_file(
+    program_state: &ProgramState,
+    mut season_path: PathBuf,
+    carry_over: SeasonCarryOver,
+) -> Result<(), String> {
+    let competition_path = match program_state.competition.absolute_file_path.as_ref() {
+        Some(path) => path.clone(),
+        None => return Err(String::from("The competition has no file path!")),
+    };
+
+    season_path.set_extension("json");
+    if season_path == competition_path {
+        return Err(String::from(
+            "The season file must not be the competition file!",
+        ));
+    }
+
+    // extend the season file if it already exists, else create a new season
+    // the carry-over is fixed when the season is created, otherwise the previous matchdays would be rescored
+    let mut season = if season_path.exists() {
+        let season = SeasonData::read_from_file(&season_path)?;
+        if season.carry_over != carry_over {
+            return Err(format!(
+                "The season counts {}, but {} is selected in Season > Point carry-over. Select the carry-over of the season to add the competition.",
+                season.carry_over.to_string().to_lowercase(),
+                carry_over.to_string().to_lowercase()
+            ));
+        }
+        season
+    } else {
+        let mut season = SeasonData::new(match season_path.file_stem() {
+            Some(stem) => stem.to_string_lossy().replace("_", " "),
+            None => String::from("Saison"),
+        });
+        season.carry_over = carry_over;
+        season
+    };
+
+    season.add_competition_reference(competition_path);
+    season.save_to_file(&season_path)
+}
+
+// helper for showing error messages
+fn show_error_message(function: &str, msg: String) {
+    match native_dialog::MessageDialog::new()
+        .set_type(MessageType::Error)
+        .set_title("Error occurred!")
+        .set_text(
+            format!("An error occurred during the season operation.\nError Message:\n{msg}")
+                .as_str(),
+        )
+        .show_alert()
+    {
+        Ok(_) => (),
+        Err(_) => eprintln!("[{function}]: Could not open message dialog! Error message: {msg}"),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_season_result() {
+    use super::two_team_competition;
+
+    // the first matchday is won by SV Musterstadt, TSV Beispiel misses the second one
+    let mut first_matchday = two_team_competition();
+    first_matchday.matches[0][0].set_points([7, 3]);
+    let mut second_matchday = two_team_competition();
+    second_matchday.teams.as_mut().unwrap()[0][0].name = String::from("SV Musterstadt ");
+    second_matchday.teams.as_mut().unwrap()[0][1].name = String::from("FC Gast");
+    second_matchday.matches[0][0].set_points([4, 9]);
+    let matchdays = vec![first_matchday, second_matchday];
+
+    let mut season = SeasonData::new(String::from("Saison 2023"));
+    let table = season.calc_season_result(&matchdays);
+    let team_names: Vec<&str> = table.iter().map(|entry| entry.team_name.as_str()).collect();
+    assert_eq!(
+        team_names,
+        vec!["FC Gast", "SV Musterstadt", "TSV Beispiel"]
+    );
+    assert_eq!(table[0].matchday_points, vec![None, Some(2)]);
+    assert_eq!(table[1].matchday_points, vec![Some(2), Some(0)]);
+    assert_eq!(table[1].points, 2);
+    assert_eq!(table[1].match_points, [2, 2]);
+    assert_eq!(table[1].stock_points, [11, 12]);
+    assert_eq!(table[2].matchday_points, vec![Some(0), None]);
+
+    // the winner of a group of two gets 2 placement points, the last team 1
+    season.carry_over = SeasonCarryOver::PlacementPoints;
+    let table = season.calc_season_result(&matchdays);
+    let team_names: Vec<&str> = table.iter().map(|entry| entry.team_name.as_str()).collect();
+    assert_eq!(
+        team_names,
+        vec!["SV Musterstadt", "FC Gast", "TSV Beispiel"]
+    );
+    assert_eq!(table[0].matchday_points, vec![Some(2), Some(1)]);
+    assert_eq!(table[0].points, 3);
+    assert_eq!(table[2].points, 1);
+
+    let latex = season.get_season_result_as_latex(&matchdays).unwrap();
+    assert!(latex.contains(r"\small Kreis & \small 1. & \small 2. & \small Punkte"));
+    assert!(latex.contains(r"\large Kreis 2 & \small 1 & \small -- & \large 1"));
+
+    // a season without matchdays has no matchday columns
+    let latex = season.get_season_result_as_latex(&vec![]).unwrap();
+    assert!(!latex.contains("*{0}"));
+    assert!(latex.contains(r"\small Kreis & \small Punkte"));
+}
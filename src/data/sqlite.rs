@@ -0,0 +1,499 @@
+use std::{fs, path::Path};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use super::{
+    migration::CURRENT_FORMAT_VERSION, CompetitionData, Match, Team, DEFAULT_BATCH_DURATION_MINUTES,
+};
+
+// The SQLite backend stores the competition data in tables instead of a single JSON document.
+// Every database file contains one competition, the competition id leaves room for several categories per file.
+
+const COMPETITION_ID: i64 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE competition (
+    id INTEGER PRIMARY KEY,
+    version INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    date_string TEXT NOT NULL,
+    place TEXT NOT NULL,
+    executor TEXT NOT NULL,
+    organizer TEXT NOT NULL,
+    referee TEXT NOT NULL,
+    competition_manager TEXT NOT NULL,
+    clerk TEXT NOT NULL,
+    additional_text TEXT NOT NULL,
+    count_teams INTEGER NOT NULL,
+    count_groups INTEGER NOT NULL,
+    count_teams_per_group INTEGER NOT NULL,
+    with_break INTEGER NOT NULL,
+    region_scoring TEXT NOT NULL,
+    has_teams INTEGER NOT NULL,
+    has_group_names INTEGER NOT NULL,
+    start_time TEXT NOT NULL,
+    batch_duration_minutes INTEGER NOT NULL
+);
+CREATE TABLE groups (
+    competition_id INTEGER NOT NULL REFERENCES competition(id),
+    group_idx INTEGER NOT NULL,
+    name TEXT,
+    current_batch INTEGER,
+    has_teams INTEGER NOT NULL,
+    has_matches INTEGER NOT NULL,
+    PRIMARY KEY (competition_id, group_idx)
+);
+CREATE TABLE teams (
+    competition_id INTEGER NOT NULL REFERENCES competition(id),
+    group_idx INTEGER NOT NULL,
+    team_idx INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    region TEXT NOT NULL,
+    license_number TEXT NOT NULL,
+    PRIMARY KEY (competition_id, group_idx, team_idx)
+);
+CREATE TABLE players (
+    competition_id INTEGER NOT NULL REFERENCES competition(id),
+    group_idx INTEGER NOT NULL,
+    team_idx INTEGER NOT NULL,
+    player_idx INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (competition_id, group_idx, team_idx, player_idx)
+);
+CREATE TABLE matches (
+    competition_id INTEGER NOT NULL REFERENCES competition(id),
+    group_idx INTEGER NOT NULL,
+    match_idx INTEGER NOT NULL,
+    team_a INTEGER NOT NULL,
+    team_b INTEGER NOT NULL,
+    points_a INTEGER,
+    points_b INTEGER,
+    result TEXT NOT NULL,
+    batch INTEGER NOT NULL,
+    lane INTEGER NOT NULL,
+    PRIMARY KEY (competition_id, group_idx, match_idx)
+);
+";
+
+fn db_err(err: rusqlite::Error) -> String {
+    format!("Database error: {err}")
+}
+
+// the enums are stored with their serde names, the same as in the JSON format
+fn enum_to_text<T: Serialize>(value: &T) -> Result<String, String> {
+    match serde_json::to_value(value) {
+        Ok(Value::String(text)) => Ok(text),
+        _ => Err(String::from("Serialization of an enum value failed!")),
+    }
+}
+
+fn enum_from_text<T: DeserializeOwned>(text: String) -> Result<T, String> {
+    match serde_json::from_value(Value::String(text.clone())) {
+        Ok(value) => Ok(value),
+        Err(_) => Err(format!("The database contains an unknown value: {text}")),
+    }
+}
+
+// creates the database file content for the competition data
+pub fn build_database(file_path: &Path, data: &CompetitionData) -> Result<Vec<u8>, String> {
+    // SQLite can only write to files, therefore build the database next to the target and read it back
+    let file_name = match file_path.file_name() {
+        Some(file_name) => file_name.to_string_lossy().to_string(),
+        None => return Err(String::from("Path has no file name!")),
+    };
+    let tmp_path = file_path.with_file_name(format!(".{file_name}.build"));
+    let _ = fs::remove_file(&tmp_path);
+
+    let write_res = write_database(&tmp_path, data);
+    let content = write_res.and_then(|_| match fs::read(&tmp_path) {
+        Ok(content) => Ok(content),
+        Err(_) => Err(format!("Error whilst reading file: {}", tmp_path.display())),
+    });
+    let _ = fs::remove_file(&tmp_path);
+    content
+}
+
+fn write_database(path: &Path, data: &CompetitionData) -> Result<(), String> {
+    let mut connection = Connection::open(path).map_err(db_err)?;
+    connection.execute_batch(SCHEMA).map_err(db_err)?;
+
+    let transaction = connection.transaction().map_err(db_err)?;
+    insert_competition(&transaction, data)?;
+    transaction.commit().map_err(db_err)
+}
+
+fn insert_competition(transaction: &Transaction, data: &CompetitionData) -> Result<(), String> {
+    transaction
+        .execute(
+            "INSERT INTO competition VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+            params![
+                COMPETITION_ID,
+                CURRENT_FORMAT_VERSION as i64,
+                data.name,
+                data.date_string,
+                data.place,
+                data.executor,
+                data.organizer,
+                data.referee,
+                data.competition_manager,
+                data.clerk,
+                data.additional_text,
+                data.count_teams,
+                data.team_distribution[0],
+                data.team_distribution[1],
+                data.with_break,
+                enum_to_text(&data.region_scoring)?,
+                data.teams.is_some(),
+                data.group_names.is_some(),
+                data.start_time,
+                data.batch_duration_minutes,
+            ],
+        )
+        .map_err(db_err)?;
+
+    // the vectors per group may have different lengths while the competition is set up
+    let count_groups = [
+        data.current_batch.len(),
+        data.matches.len(),
+        data.group_names
+            .as_ref()
+            .map_or(0, |group_names| group_names.len()),
+        data.teams.as_ref().map_or(0, |teams| teams.len()),
+    ]
+    .into_iter()
+    .max()
+    .unwrap();
+    for group_idx in 0..count_groups {
+        let group_name = data
+            .group_names
+            .as_ref()
+            .and_then(|group_names| group_names.get(group_idx));
+        transaction
+            .execute(
+                "INSERT INTO groups VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    COMPETITION_ID,
+                    group_idx as i64,
+                    group_name,
+                    data.current_batch.get(group_idx),
+                    data.teams
+                        .as_ref()
+                        .map_or(false, |teams| group_idx < teams.len()),
+                    group_idx < data.matches.len(),
+                ],
+            )
+            .map_err(db_err)?;
+    }
+
+    for (group_idx, teams) in data.teams.iter().flatten().enumerate() {
+        for (team_idx, team) in teams.iter().enumerate() {
+            transaction
+                .execute(
+                    "INSERT INTO teams VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        COMPETITION_ID,
+                        group_idx as i64,
+                        team_idx as i64,
+                        team.name,
+                        team.region,
+                        team.license_number
+                    ],
+                )
+                .map_err(db_err)?;
+
+            for (player_idx, player_name) in team.player_names.iter().enumerate() {
+                if let Some(player_name) = player_name {
+                    transaction
+                        .execute(
+                            "INSERT INTO players VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![
+                                COMPETITION_ID,
+                                group_idx as i64,
+                                team_idx as i64,
+                                player_idx as i64,
+                                player_name
+                            ],
+                        )
+                        .map_err(db_err)?;
+                }
+            }
+        }
+    }
+
+    for (group_idx, matches) in data.matches.iter().enumerate() {
+        for (match_idx, _match) in matches.iter().enumerate() {
+            transaction
+                .execute(
+                    "INSERT INTO matches VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        COMPETITION_ID,
+                        group_idx as i64,
+                        match_idx as i64,
+                        _match.team_a as i64,
+                        _match.team_b as i64,
+                        _match.points.map(|points| points[0]),
+                        _match.points.map(|points| points[1]),
+                        enum_to_text(&_match.result)?,
+                        _match.batch,
+                        _match.lane,
+                    ],
+                )
+                .map_err(db_err)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn read_from_database(path: &Path) -> Result<CompetitionData, String> {
+    let connection = Connection::open(path).map_err(db_err)?;
+
+    let competition_row = connection
+        .query_row(
+            "SELECT version, name, date_string, place, executor, organizer, referee, competition_manager, clerk,
+                additional_text, count_teams, count_groups, count_teams_per_group, with_break, region_scoring,
+                has_teams, has_group_names
+            FROM competition WHERE id = ?1",
+            params![COMPETITION_ID],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    CompetitionData {
+                        name: row.get(1)?,
+                        date_string: row.get(2)?,
+                        place: row.get(3)?,
+                        executor: row.get(4)?,
+                        organizer: row.get(5)?,
+                        referee: row.get(6)?,
+                        competition_manager: row.get(7)?,
+                        clerk: row.get(8)?,
+                        additional_text: row.get(9)?,
+                        count_teams: row.get(10)?,
+                        team_distribution: [row.get(11)?, row.get(12)?],
+                        teams: if row.get(15)? { Some(vec![]) } else { None },
+                        group_names: if row.get(16)? { Some(vec![]) } else { None },
+                        matches: vec![],
+                        current_batch: vec![],
+                        with_break: row.get(13)?,
+                        region_scoring: Default::default(),
+                        start_time: String::new(),
+                        batch_duration_minutes: DEFAULT_BATCH_DURATION_MINUTES,
+                    },
+                    row.get::<_, String>(14)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(db_err)?;
+
+    let (version, mut data, region_scoring) = match competition_row {
+        Some(row) => row,
+        None => return Err(String::from("The database does not contain a competition!")),
+    };
+    if version as u64 > CURRENT_FORMAT_VERSION {
+        return Err(format!(
+            "This file was created by a newer version of ISRAT (file format version {version}, this version supports up to {CURRENT_FORMAT_VERSION}).\nPlease update ISRAT to open it."
+        ));
+    }
+    data.region_scoring = enum_from_text(region_scoring)?;
+
+    // databases of version 1 have no planned times yet
+    if version >= 2 {
+        let (start_time, batch_duration_minutes) = connection
+            .query_row(
+                "SELECT start_time, batch_duration_minutes FROM competition WHERE id = ?1",
+                params![COMPETITION_ID],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(db_err)?;
+        data.start_time = start_time;
+        data.batch_duration_minutes = batch_duration_minutes;
+    }
+
+    // groups
+    let mut statement = connection
+        .prepare("SELECT name, current_batch, has_teams, has_matches FROM groups WHERE competition_id = ?1 ORDER BY group_idx")
+        .map_err(db_err)?;
+    let groups = statement
+        .query_map(params![COMPETITION_ID], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<u32>>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, bool>(3)?,
+            ))
+        })
+        .map_err(db_err)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_err)?;
+    for (group_name, current_batch, has_teams, has_matches) in groups {
+        if let (Some(group_names), Some(group_name)) = (data.group_names.as_mut(), group_name) {
+            group_names.push(group_name);
+        }
+        if let Some(current_batch) = current_batch {
+            data.current_batch.push(current_batch);
+        }
+        if let (Some(teams), true) = (data.teams.as_mut(), has_teams) {
+            teams.push(vec![]);
+        }
+        if has_matches {
+            data.matches.push(vec![]);
+        }
+    }
+
+    // teams and their players
+    if let Some(teams) = data.teams.as_mut() {
+        // databases before version 3 have no license numbers
+        let license_number_column = if version >= 3 { "license_number" } else { "''" };
+        let mut statement = connection
+            .prepare(&format!("SELECT group_idx, name, region, {license_number_column} FROM teams WHERE competition_id = ?1 ORDER BY group_idx, team_idx"))
+            .map_err(db_err)?;
+        let rows = statement
+            .query_map(params![COMPETITION_ID], |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    Team {
+                        name: row.get(1)?,
+                        region: row.get(2)?,
+                        player_names: Default::default(),
+                        license_number: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(db_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        for (group_idx, team) in rows {
+            match teams.get_mut(group_idx) {
+                Some(group) => group.push(team),
+                None => return Err(format!("The team {} has an unknown group!", team.name)),
+            }
+        }
+
+        let mut statement = connection
+            .prepare("SELECT group_idx, team_idx, player_idx, name FROM players WHERE competition_id = ?1")
+            .map_err(db_err)?;
+        let rows = statement
+            .query_map(params![COMPETITION_ID], |row| {
+                Ok((
+                    row.get::<_, i64>(0)? as usize,
+                    row.get::<_, i64>(1)? as usize,
+                    row.get::<_, i64>(2)? as usize,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(db_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        for (group_idx, team_idx, player_idx, player_name) in rows {
+            match teams
+                .get_mut(group_idx)
+                .and_then(|group| group.get_mut(team_idx))
+                .and_then(|team| team.player_names.get_mut(player_idx))
+            {
+                Some(player) => *player = Some(player_name),
+                None => return Err(format!("The player {player_name} has an unknown team!")),
+            }
+        }
+    }
+
+    // matches
+    let mut statement = connection
+        .prepare("SELECT group_idx, team_a, team_b, points_a, points_b, result, batch, lane FROM matches WHERE competition_id = ?1 ORDER BY group_idx, match_idx")
+        .map_err(db_err)?;
+    let rows = statement
+        .query_map(params![COMPETITION_ID], |row| {
+            Ok((
+                row.get::<_, i64>(0)? as usize,
+                row.get::<_, i64>(1)? as usize,
+                row.get::<_, i64>(2)? as usize,
+                row.get::<_, Option<i32>>(3)?,
+                row.get::<_, Option<i32>>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, u32>(6)?,
+                row.get::<_, u32>(7)?,
+            ))
+        })
+        .map_err(db_err)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_err)?;
+    for (group_idx, team_a, team_b, points_a, points_b, result, batch, lane) in rows {
+        let _match = Match {
+            team_a,
+            team_b,
+            points: points_a.zip(points_b).map(|(a, b)| [a, b]),
+            result: enum_from_text(result)?,
+            batch,
+            lane,
+        };
+        match data.matches.get_mut(group_idx) {
+            Some(matches) => matches.push(_match),
+            None => return Err(String::from("A match has an unknown group!")),
+        }
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+#[test]
+fn test_database_round_trip() {
+    use super::{
+        read_write::{read_from_file, save_to_file},
+        MatchResult, RegionScoring,
+    };
+    use std::path::PathBuf;
+
+    let json_path = PathBuf::from("./tmp/documents/sqlite_test/competition.json");
+    let db_path = json_path.with_extension("sqlite");
+    let _ = fs::remove_dir_all(json_path.parent().unwrap());
+
+    let mut data = CompetitionData::empty();
+    data.name = String::from("Datenbankmeisterschaft");
+    data.additional_text = String::from("Zeile 1\n\"Zeile\" 2");
+    data.team_distribution = [1, 2];
+    data.region_scoring = RegionScoring::MatchPoints;
+    data.group_names = Some(vec![String::from("Gruppe A")]);
+    data.teams = Some(vec![vec![
+        Team {
+            name: String::from("Team 1"),
+            region: String::from("Kreis 1"),
+            player_names: [
+                Some(String::from("Spieler 1")),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
+            license_number: String::from("BY-1234"),
+        },
+        Team {
+            name: String::from("Team 2"),
+            region: String::from("Kreis 2"),
+            player_names: Default::default(),
+            license_number: String::from(""),
+        },
+    ]]);
+    data.current_batch = vec![1];
+    data.matches = vec![vec![Match {
+        team_a: 0,
+        team_b: 1,
+        points: Some([7, 3]),
+        result: MatchResult::WinnerA,
+        batch: 0,
+        lane: 0,
+    }]];
+
+    // importing from and exporting to JSON is saving the data with the other extension
+    assert!(save_to_file(db_path.clone(), &data, 0).is_ok());
+    let read_data = read_from_file(db_path).unwrap();
+    assert!(save_to_file(json_path.clone(), &read_data, 0).is_ok());
+
+    assert_eq!(
+        read_data.get_as_json_string().unwrap(),
+        data.get_as_json_string().unwrap()
+    );
+    assert_eq!(read_from_file(json_path).unwrap().name, data.name);
+}
@@ -0,0 +1,396 @@
+use std::{collections::HashSet, fmt::Display, path::Path, sync::mpsc, thread};
+
+use native_dialog::FileDialog;
+
+use crate::ProgramState;
+
+use super::{CompetitionData, Team};
+
+// The meaning of a column of the imported CSV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportColumn {
+    Ignore,
+    TeamName,
+    Region,
+    Group,
+    Player(usize), // the index of the player, at most 6 players per team
+}
+
+impl ImportColumn {
+    pub fn all() -> Vec<ImportColumn> {
+        let mut all = vec![
+            ImportColumn::Ignore,
+            ImportColumn::TeamName,
+            ImportColumn::Region,
+            ImportColumn::Group,
+        ];
+        all.extend((0..6).map(ImportColumn::Player));
+        all
+    }
+
+    // guesses the meaning of a column from its header, e.g. "Mannschaft" or "Spieler 2"
+    fn guess(header: &str) -> ImportColumn {
+        let header = header.trim().to_lowercase();
+        let player_number = header
+            .chars()
+            .filter(|c| c.is_ascii_digit())
+            .collect::<String>()
+            .parse::<usize>()
+            .ok();
+
+        if header.contains("player") || header.contains("spieler") {
+            match player_number {
+                Some(number) if (1..=6).contains(&number) => ImportColumn::Player(number - 1),
+                _ => ImportColumn::Ignore,
+            }
+        } else if header.contains("region") || header.contains("kreis") {
+            ImportColumn::Region
+        } else if header.contains("group") || header.contains("gruppe") {
+            ImportColumn::Group
+        } else if header.contains("team") || header.contains("mannschaft") || header == "name" {
+            ImportColumn::TeamName
+        } else {
+            ImportColumn::Ignore
+        }
+    }
+}
+
+impl Display for ImportColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportColumn::Ignore => write!(f, "Ignore"),
+            ImportColumn::TeamName => write!(f, "Team name"),
+            ImportColumn::Region => write!(f, "Region"),
+            ImportColumn::Group => write!(f, "Group"),
+            ImportColumn::Player(idx) => write!(f, "Player {}", idx + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportRowStatus {
+    Ok,
+    MissingName, // the row has no team name and is skipped
+    Duplicate,   // a team with the same name was already imported, the row is skipped
+    Overflow,    // there is no place left for the team, either in its group or in the competition
+}
+
+impl Display for ImportRowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ImportRowStatus::Ok => "OK",
+                ImportRowStatus::MissingName => "Missing team name",
+                ImportRowStatus::Duplicate => "Duplicate",
+                ImportRowStatus::Overflow => "Too many teams",
+            }
+        )
+    }
+}
+
+// a row of the CSV file interpreted with the current column mapping
+pub struct ImportRow {
+    pub team: Team,
+    pub group_idx: Option<usize>, // the group the team is placed in, None if the row is skipped
+    pub group_name: Option<String>,
+    pub status: ImportRowStatus,
+}
+
+pub struct TeamImport {
+    pub headers: Vec<String>,
+    pub records: Vec<Vec<String>>,
+    pub columns: Vec<ImportColumn>, // the meaning of each column, adjustable by the user
+}
+
+impl TeamImport {
+    // parses the CSV content, the first line has to contain the headers
+    pub fn parse(content: &str) -> Result<Self, String> {
+        // spreadsheets with german locale use semicolons as delimiter
+        let first_line = content.lines().next().unwrap_or("");
+        let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+            b';'
+        } else {
+            b','
+        };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+
+        let headers: Vec<String> = match reader.headers() {
+            Ok(headers) => headers.iter().map(String::from).collect(),
+            Err(err) => return Err(format!("The CSV file has no valid header line: {err}")),
+        };
+
+        let mut records = vec![];
+        for record in reader.records() {
+            match record {
+                Ok(record) => records.push(record.iter().map(String::from).collect()),
+                Err(err) => return Err(format!("The CSV file is not well-formatted: {err}")),
+            }
+        }
+
+        if headers.is_empty() || records.is_empty() {
+            return Err(String::from("The CSV file contains no teams!"));
+        }
+
+        let columns = headers
+            .iter()
+            .map(|header| ImportColumn::guess(header))
+            .collect();
+
+        Ok(TeamImport {
+            headers,
+            records,
+            columns,
+        })
+    }
+
+    // interprets the records with the current column mapping and assigns them to the groups of the competition
+    pub fn rows(&self, data: &CompetitionData) -> Vec<ImportRow> {
+        let [count_groups, teams_per_group] = data.team_distribution;
+        let has_group_column = self.columns.contains(&ImportColumn::Group);
+
+        let mut seen_names = HashSet::new();
+        let mut group_names: Vec<String> = vec![]; // the group names in order of their first appearance
+        let mut group_sizes = vec![0; count_groups as usize];
+        let mut count_imported = 0;
+
+        self.records
+            .iter()
+            .map(|record| {
+                let mut team = Team {
+                    name: String::new(),
+                    region: String::new(),
+                    player_names: [None, None, None, None, None, None],
+                    license_number: String::from(""),
+                };
+                let mut group_name = None;
+
+                record
+                    .iter()
+                    .zip(self.columns.iter())
+                    .for_each(|(value, column)| match column {
+                        ImportColumn::Ignore => (),
+                        ImportColumn::TeamName => team.name = value.clone(),
+                        ImportColumn::Region => team.region = value.clone(),
+                        ImportColumn::Group => group_name = Some(value.clone()),
+                        ImportColumn::Player(idx) if !value.is_empty() => {
+                            team.player_names[*idx] = Some(value.clone())
+                        }
+                        ImportColumn::Player(_) => (),
+                    });
+
+                let status = if team.name.is_empty() {
+                    ImportRowStatus::MissingName
+                } else if !seen_names.insert(team.name.to_lowercase()) {
+                    ImportRowStatus::Duplicate
+                } else {
+                    ImportRowStatus::Ok
+                };
+
+                // without a group column the teams fill the groups one after another
+                let group_idx = match (status, group_name.as_ref()) {
+                    (ImportRowStatus::Ok, Some(name)) if has_group_column => {
+                        match group_names.iter().position(|group_name| group_name == name) {
+                            Some(group_idx) => Some(group_idx),
+                            None => {
+                                group_names.push(name.clone());
+                                Some(group_names.len() - 1)
+                            }
+                        }
+                    }
+                    (ImportRowStatus::Ok, _) if teams_per_group > 0 => {
+                        Some(count_imported / teams_per_group as usize)
+                    }
+                    _ => None,
+                };
+
+                let status = match group_idx {
+                    Some(group_idx)
+                        if group_idx >= count_groups as usize
+                            || group_sizes[group_idx] >= teams_per_group
+                            || count_imported >= data.count_teams as usize =>
+                    {
+                        ImportRowStatus::Overflow
+                    }
+                    Some(group_idx) => {
+                        group_sizes[group_idx] += 1;
+                        count_imported += 1;
+                        status
+                    }
+                    None => status,
+                };
+
+                ImportRow {
+                    team,
+                    group_idx: if status == ImportRowStatus::Ok {
+                        group_idx
+                    } else {
+                        None
+                    },
+                    group_name: group_name.filter(|_| has_group_column),
+                    status,
+                }
+            })
+            .collect()
+    }
+
+    // replaces the teams and group names of the competition by the imported ones, returns the count of imported teams
+    pub fn apply(&self, data: &mut CompetitionData) -> usize {
+        let [count_groups, teams_per_group] = data.team_distribution;
+        let empty_team = Team {
+            name: String::new(),
+            region: String::new(),
+            player_names: [None, None, None, None, None, None],
+            license_number: String::from(""),
+        };
+        let mut teams: Vec<Vec<Team>> = vec![vec![]; count_groups as usize];
+        let mut group_names: Vec<String> = (1..=count_groups)
+            .map(|group_idx| format!("Group {group_idx}"))
+            .collect();
+
+        let rows = self.rows(data);
+        rows.iter().for_each(|row| {
+            if let Some(group_idx) = row.group_idx {
+                teams[group_idx].push(row.team.clone());
+                if let Some(group_name) = row.group_name.as_ref() {
+                    group_names[group_idx] = group_name.clone();
+                }
+            }
+        });
+
+        // the remaining places are entered by hand
+        teams
+            .iter_mut()
+            .for_each(|group| group.resize(teams_per_group as usize, empty_team.clone()));
+
+        data.teams = Some(teams);
+        data.group_names = Some(group_names);
+        rows.iter().filter(|row| row.group_idx.is_some()).count()
+    }
+}
+
+pub fn read_team_import(path: &Path) -> Result<TeamImport, String> {
+    match std::fs::read(path) {
+        // spreadsheet programs do not always write UTF-8, therefore replace invalid characters instead of failing
+        Ok(bytes) => TeamImport::parse(&String::from_utf8_lossy(&bytes)),
+        Err(_) => Err(format!("Error whilst reading file: {}", path.display())),
+    }
+}
+
+// opens an open dialog to choose the CSV file with the registered teams
+pub fn import_teams_action(program_state: &mut ProgramState) {
+    let (tx, rx) = mpsc::channel();
+    let absolute_dir_path = program_state.competition.absolute_dir_path.clone();
+
+    // open os open file dialog in separate thread in order to not stop the GUI rendering
+    thread::Builder::new()
+        .name(String::from("Team import dialog thread"))
+        .spawn(move || {
+            let mut dialog = FileDialog::new().add_filter("CSV Files", &["csv", "txt"]);
+
+            if let Some(dir_path) = absolute_dir_path.as_ref() {
+                dialog = dialog.set_location(dir_path);
+            }
+
+            let dialog_res = dialog.show_open_single_file();
+
+            // inform main (GUI) Thread about closed dialog
+            tx.send(dialog_res)
+                .expect("Channel is closed, this is not expected");
+        })
+        .expect("This shouldn't happen!");
+
+    program_state.threads.team_import_channels.push(rx);
+}
+
+pub fn check_team_import_threads_messages(program_state: &mut ProgramState) {
+    // check if any of the team import threads send a new message and remove the corresponding entry iff the thread has finished its work
+    let mut i = 0;
+    while i < program_state.threads.team_import_channels.len() {
+        let path_res = match program_state.threads.team_import_channels[i].try_recv() {
+            Ok(path_res) => path_res,
+            Err(_) => {
+                i += 1;
+                continue;
+            }
+        };
+
+        // the preview is shown by the team stage of the new screen
+        if let Some(new_screen_state) = program_state.new_screen_state.as_mut() {
+            match path_res {
+                Ok(Some(path)) => match read_team_import(&path) {
+                    Ok(team_import) => {
+                        new_screen_state.team_import = Some(team_import);
+                        new_screen_state.team_import_err_msg = None;
+                    }
+                    Err(msg) => new_screen_state.team_import_err_msg = Some(msg),
+                },
+                Ok(None) => eprintln!("[import_teams_action]: FileDialog returned None path!"),
+                Err(msg) => new_screen_state.team_import_err_msg = Some(msg.to_string()),
+            }
+        }
+
+        program_state.threads.team_import_channels.remove(i);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_team_import() {
+    let content = "Mannschaft;Kreis;Gruppe;Spieler 1;Spieler 2\n\
+        SV Musterstadt;Kreis 1;A;Max;Moritz\n\
+        TSV Beispiel;Kreis 2;B;;\n\
+        sv musterstadt;Kreis 1;A;;\n\
+        FC Test;Kreis 1;A;Anna;\n\
+        ;Kreis 3;B;;\n\
+        SC Drei;Kreis 3;A;;\n";
+
+    let mut data = CompetitionData::empty();
+    data.count_teams = 4;
+    data.team_distribution = [2, 2];
+
+    let team_import = TeamImport::parse(content).unwrap();
+    assert_eq!(
+        team_import.columns,
+        vec![
+            ImportColumn::TeamName,
+            ImportColumn::Region,
+            ImportColumn::Group,
+            ImportColumn::Player(0),
+            ImportColumn::Player(1)
+        ]
+    );
+
+    let statuses: Vec<ImportRowStatus> = team_import
+        .rows(&data)
+        .iter()
+        .map(|row| row.status)
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ImportRowStatus::Ok,
+            ImportRowStatus::Ok,
+            ImportRowStatus::Duplicate,
+            ImportRowStatus::Ok,
+            ImportRowStatus::MissingName,
+            ImportRowStatus::Overflow, // group A has only 2 places
+        ]
+    );
+
+    assert_eq!(team_import.apply(&mut data), 3);
+    let teams = data.teams.as_ref().unwrap();
+    assert_eq!(
+        data.group_names,
+        Some(vec![String::from("A"), String::from("B")])
+    );
+    assert_eq!(teams[0][1].name, "FC Test");
+    assert_eq!(teams[0][0].player_names[1], Some(String::from("Moritz")));
+    assert_eq!(teams[1][1].name, ""); // left for manual input
+}
@@ -0,0 +1,153 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use minijinja::Environment;
+use serde::Serialize;
+
+use crate::settings::config_dir;
+
+// The LaTeX templates of the PDF exports. The built-in templates can be overridden by a file with the same
+// name in the templates folder of the config directory, e.g. to change a column width or a label.
+
+pub const HEADER_TEMPLATE: &str = "header.tex";
+pub const RESULT_LIST_TEMPLATE: &str = "result_list.tex";
+pub const START_LIST_TEMPLATE: &str = "start_list.tex";
+pub const REGION_RESULT_TEMPLATE: &str = "region_result.tex";
+pub const TEAM_MATCH_PLANS_TEMPLATE: &str = "team_match_plans.tex";
+pub const LANE_MATCH_PLANS_TEMPLATE: &str = "lane_match_plans.tex";
+pub const SEASON_TABLE_TEMPLATE: &str = "season_table.tex";
+
+const BUILTIN_TEMPLATES: [(&str, &str); 7] = [
+    (
+        HEADER_TEMPLATE,
+        include_str!("../../resources/templates/header.tex"),
+    ),
+    (
+        RESULT_LIST_TEMPLATE,
+        include_str!("../../resources/templates/result_list.tex"),
+    ),
+    (
+        START_LIST_TEMPLATE,
+        include_str!("../../resources/templates/start_list.tex"),
+    ),
+    (
+        REGION_RESULT_TEMPLATE,
+        include_str!("../../resources/templates/region_result.tex"),
+    ),
+    (
+        TEAM_MATCH_PLANS_TEMPLATE,
+        include_str!("../../resources/templates/team_match_plans.tex"),
+    ),
+    (
+        LANE_MATCH_PLANS_TEMPLATE,
+        include_str!("../../resources/templates/lane_match_plans.tex"),
+    ),
+    (
+        SEASON_TABLE_TEMPLATE,
+        include_str!("../../resources/templates/season_table.tex"),
+    ),
+];
+
+// the folder searched for user templates
+pub fn user_template_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("templates"))
+}
+
+// the sources of all templates, a user template replaces the built-in one with the same name
+fn load_templates(user_dir: Option<&Path>) -> Result<Vec<(&'static str, String)>, String> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, builtin_source)| {
+            match user_dir
+                .map(|dir| dir.join(name))
+                .filter(|path| path.is_file())
+            {
+                Some(path) => match fs::read_to_string(&path) {
+                    Ok(source) => Ok((*name, source)),
+                    Err(_) => Err(format!("Reading the template {} failed!", path.display())),
+                },
+                None => Ok((*name, String::from(*builtin_source))),
+            }
+        })
+        .collect()
+}
+
+fn render_with_sources<S: Serialize>(
+    sources: &[(&'static str, String)],
+    name: &str,
+    context: S,
+) -> Result<String, String> {
+    let mut env = Environment::new();
+    for (template_name, source) in sources {
+        env.add_template(template_name, source)
+            .map_err(|err| format!("The template {template_name} is invalid: {err}"))?;
+    }
+
+    env.get_template(name)
+        .and_then(|template| template.render(context))
+        .map_err(|err| format!("Rendering the template {name} failed: {err}"))
+}
+
+// renders the template with the given name, the context is the structured data of the document
+pub fn render_template<S: Serialize>(name: &str, context: S) -> Result<String, String> {
+    let sources = load_templates(user_template_dir().as_deref())?;
+    render_with_sources(&sources, name, context)
+}
+
+// copies the built-in templates into the user template folder as starting point for own templates,
+// existing user templates are kept
+pub fn write_default_templates() -> Result<PathBuf, String> {
+    let template_dir = match user_template_dir() {
+        Some(template_dir) => template_dir,
+        None => return Err(String::from("Could not determine the config directory!")),
+    };
+    if fs::create_dir_all(&template_dir).is_err() {
+        return Err(String::from("Creation of the template directory failed!"));
+    }
+
+    for (name, source) in BUILTIN_TEMPLATES {
+        let path = template_dir.join(name);
+        if !path.exists() && fs::write(&path, source).is_err() {
+            return Err(format!("Write to {} failed!", path.display()));
+        }
+    }
+    Ok(template_dir)
+}
+
+#[cfg(test)]
+#[test]
+fn test_user_templates() {
+    use minijinja::context;
+
+    let user_dir = std::env::temp_dir().join(format!("israt-templates-{}", std::process::id()));
+    fs::create_dir_all(&user_dir).unwrap();
+    fs::write(
+        user_dir.join(HEADER_TEMPLATE),
+        r"\textbf{Eigener Kopf {{ competition.name }}}",
+    )
+    .unwrap();
+
+    let sources = load_templates(Some(&user_dir)).unwrap();
+    fs::remove_dir_all(&user_dir).unwrap();
+    assert_eq!(sources.len(), BUILTIN_TEMPLATES.len());
+
+    // the other templates include the overridden header
+    let latex = render_with_sources(
+        &sources,
+        REGION_RESULT_TEMPLATE,
+        context! {
+            competition => context! { name => "Kreismeisterschaft" },
+            region_scoring => "MatchPoints",
+            regions => Vec::<()>::new(),
+        },
+    )
+    .unwrap();
+    assert!(latex.contains(r"\textbf{Eigener Kopf Kreismeisterschaft}"));
+    assert!(latex.contains(r"\LARGE \textbf{Kreiswertung}"));
+
+    // errors in user templates are reported instead of panicking
+    let broken_sources = vec![(HEADER_TEMPLATE, String::from("{% if %}"))];
+    assert!(render_with_sources(&broken_sources, HEADER_TEMPLATE, ()).is_err());
+}
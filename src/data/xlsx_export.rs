@@ -0,0 +1,255 @@
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+
+use super::{CompetitionData, MatchResult};
+
+// Excel workbook of the competition: one sheet per group standings, the schedule with the results and the start list
+
+const MAX_SHEET_NAME_LEN: usize = 31; // limit of Excel
+
+fn xlsx_err(err: XlsxError) -> String {
+    format!("Writing the XLSX workbook failed: {err}")
+}
+
+// Excel forbids some characters and long names for sheets, the names also have to be unique
+fn sheet_name(name: &str, used_names: &mut Vec<String>) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .take(MAX_SHEET_NAME_LEN)
+        .collect();
+    let cleaned = cleaned.trim_matches('\'').to_string();
+
+    let mut unique = cleaned.clone();
+    let mut counter = 2;
+    while unique.is_empty()
+        || used_names
+            .iter()
+            .any(|used| used.to_lowercase() == unique.to_lowercase())
+    {
+        let suffix = format!(" ({counter})");
+        let prefix: String = cleaned
+            .chars()
+            .take(MAX_SHEET_NAME_LEN - suffix.len())
+            .collect();
+        unique = format!("{prefix}{suffix}");
+        counter += 1;
+    }
+    used_names.push(unique.clone());
+    unique
+}
+
+fn write_header(
+    worksheet: &mut Worksheet,
+    headers: &[&str],
+    format: &Format,
+) -> Result<(), String> {
+    for (col, header) in headers.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, *header, format)
+            .map_err(xlsx_err)?;
+    }
+    Ok(())
+}
+
+impl CompetitionData {
+    pub fn get_as_xlsx(&self) -> Result<Vec<u8>, String> {
+        debug_assert!(self.teams.is_some());
+
+        let mut workbook = Workbook::new();
+        let header_format = Format::new().set_bold();
+        let mut used_names = vec![];
+
+        for group_idx in 0..self.teams.as_ref().unwrap().len() {
+            let name = sheet_name(&self.group_name(group_idx), &mut used_names);
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name(name).map_err(xlsx_err)?;
+            self.write_standings_sheet(worksheet, group_idx, &header_format)?;
+        }
+
+        let name = sheet_name("Schedule", &mut used_names);
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(name).map_err(xlsx_err)?;
+        self.write_schedule_sheet(worksheet, &header_format)?;
+
+        let name = sheet_name("Start list", &mut used_names);
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(name).map_err(xlsx_err)?;
+        self.write_start_list_sheet(worksheet, &header_format)?;
+
+        workbook.save_to_buffer().map_err(xlsx_err)
+    }
+
+    fn write_standings_sheet(
+        &self,
+        worksheet: &mut Worksheet,
+        group_idx: usize,
+        header_format: &Format,
+    ) -> Result<(), String> {
+        write_header(
+            worksheet,
+            &[
+                "Place",
+                "Team",
+                "Region",
+                "Match points won",
+                "Match points lost",
+                "Quotient",
+                "Stock points won",
+                "Stock points lost",
+            ],
+            header_format,
+        )?;
+        worksheet.set_column_width(1, 30).map_err(xlsx_err)?;
+
+        let quotient_format = Format::new().set_num_format("0.000");
+        for (place_idx, entry) in self
+            .calc_interim_result_for_group(group_idx)
+            .iter()
+            .enumerate()
+        {
+            let team = &self.teams.as_ref().unwrap()[group_idx][entry.team_idx];
+            let row = place_idx as u32 + 1;
+            worksheet
+                .write_number(row, 0, (place_idx + 1) as f64)
+                .and_then(|ws| ws.write_string(row, 1, &team.name))
+                .and_then(|ws| ws.write_string(row, 2, &team.region))
+                .and_then(|ws| ws.write_number(row, 3, entry.match_points[0]))
+                .and_then(|ws| ws.write_number(row, 4, entry.match_points[1]))
+                .and_then(|ws| {
+                    ws.write_number_with_format(row, 5, entry.quotient, &quotient_format)
+                })
+                .and_then(|ws| ws.write_number(row, 6, entry.stock_points[0]))
+                .and_then(|ws| ws.write_number(row, 7, entry.stock_points[1]))
+                .map_err(xlsx_err)?;
+        }
+
+        // the status is noted below the table, so that interim standings are not mistaken for the final ones
+        let row = self.teams.as_ref().unwrap()[group_idx].len() as u32 + 2;
+        let status = if self.is_group_finished(group_idx) {
+            "Final standings"
+        } else {
+            "Interim standings"
+        };
+        worksheet.write_string(row, 0, status).map_err(xlsx_err)?;
+        Ok(())
+    }
+
+    // all matches including the breaks, the points are filled in for the played matches
+    fn write_schedule_sheet(
+        &self,
+        worksheet: &mut Worksheet,
+        header_format: &Format,
+    ) -> Result<(), String> {
+        write_header(
+            worksheet,
+            &[
+                "Group", "Batch", "Lane", "Team A", "Team B", "Points A", "Points B",
+            ],
+            header_format,
+        )?;
+        worksheet.set_column_width(3, 30).map_err(xlsx_err)?;
+        worksheet.set_column_width(4, 30).map_err(xlsx_err)?;
+
+        let mut row = 1;
+        for (group_idx, matches) in self.matches.iter().enumerate() {
+            let mut matches: Vec<_> = matches.iter().collect();
+            matches.sort_by_key(|_match| (_match.batch, _match.lane));
+
+            for _match in matches {
+                worksheet
+                    .write_string(row, 0, self.group_name(group_idx))
+                    .and_then(|ws| ws.write_number(row, 1, _match.batch + 1))
+                    .and_then(|ws| {
+                        ws.write_string(row, 3, self.team_name(group_idx, _match.team_a))
+                    })
+                    .map_err(xlsx_err)?;
+
+                if _match.result == MatchResult::Break {
+                    worksheet.write_string(row, 4, "break").map_err(xlsx_err)?;
+                } else {
+                    worksheet
+                        .write_number(row, 2, _match.lane + 1)
+                        .and_then(|ws| {
+                            ws.write_string(row, 4, self.team_name(group_idx, _match.team_b))
+                        })
+                        .map_err(xlsx_err)?;
+                }
+
+                if let Some(points) = _match.points {
+                    worksheet
+                        .write_number(row, 5, points[0])
+                        .and_then(|ws| ws.write_number(row, 6, points[1]))
+                        .map_err(xlsx_err)?;
+                }
+                row += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_start_list_sheet(
+        &self,
+        worksheet: &mut Worksheet,
+        header_format: &Format,
+    ) -> Result<(), String> {
+        write_header(
+            worksheet,
+            &[
+                "Group",
+                "Start number",
+                "Team",
+                "Region",
+                "Player 1",
+                "Player 2",
+                "Player 3",
+                "Player 4",
+                "Player 5",
+                "Player 6",
+            ],
+            header_format,
+        )?;
+        worksheet.set_column_width(2, 30).map_err(xlsx_err)?;
+
+        let mut row = 1;
+        for (group_idx, teams) in self.teams.as_ref().unwrap().iter().enumerate() {
+            for (team_idx, team) in teams.iter().enumerate() {
+                worksheet
+                    .write_string(row, 0, self.group_name(group_idx))
+                    .and_then(|ws| ws.write_number(row, 1, (team_idx + 1) as f64))
+                    .and_then(|ws| ws.write_string(row, 2, &team.name))
+                    .and_then(|ws| ws.write_string(row, 3, &team.region))
+                    .map_err(xlsx_err)?;
+                for (player_idx, player_name) in team.player_names.iter().enumerate() {
+                    if let Some(player_name) = player_name {
+                        worksheet
+                            .write_string(row, 4 + player_idx as u16, player_name)
+                            .map_err(xlsx_err)?;
+                    }
+                }
+                row += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_xlsx_export() {
+    let mut used_names = vec![];
+    assert_eq!(sheet_name("Gruppe A/B", &mut used_names), "Gruppe A_B");
+    assert_eq!(sheet_name("gruppe a/b", &mut used_names), "gruppe a_b (2)");
+    assert_eq!(
+        sheet_name("Eine sehr lange Gruppenbezeichnung", &mut used_names).len(),
+        MAX_SHEET_NAME_LEN
+    );
+
+    let data = super::two_team_competition();
+
+    // a workbook is a zip file
+    let workbook = data.get_as_xlsx().unwrap();
+    assert!(workbook.starts_with(b"PK"));
+}
@@ -2,24 +2,29 @@
 
 use std::{path::PathBuf, sync::mpsc::Receiver};
 
-use chrono::Duration;
+use beamer::BeamerState;
 use data::{
-    read_write::{
-        check_autosave_thread_messages, check_read_write_threads_messages, spawn_autosave_timer,
-    },
-    Competition, CompetitionData, Team,
+    autosave::{check_autosave, check_for_recoverable_autosaves, AutosaveState},
+    read_write::check_read_write_threads_messages,
+    season::check_season_threads_messages,
+    team_import::check_team_import_threads_messages,
+    Competition, CompetitionData, RegionScoring, Team,
 };
 use imgui::*;
 use main_menu_bar::MainMenuBarState;
 use native_dialog::Error;
 use screens::{buttons::ButtonState, erg_screen::ErgScreenState, new_screen::NewScreenState};
-use timer::{Guard, Timer};
+use server::{check_server_messages, ServerState};
+use settings::Settings;
 use winit::window::Fullscreen;
 
+mod beamer;
 mod common;
 mod data;
 mod main_menu_bar;
 mod screens;
+mod server;
+mod settings;
 mod support;
 
 fn main() {
@@ -41,13 +46,13 @@ fn main() {
         [size.width as f32, size.height as f32],
     ));
 
-    // TODO: Make interval adjustable by using GUI settings or config in home directory
-    spawn_autosave_timer(Duration::minutes(1), system.program_state.as_mut().unwrap());
-
     // TODO: Remove for productive builds
     #[cfg(debug_assertions)]
     initial_state(system.program_state.as_mut().unwrap());
 
+    // offer to restore autosaves left behind by a crash
+    check_for_recoverable_autosaves(system.program_state.as_mut().unwrap());
+
     // set color theme
     let style = system.imgui.style_mut();
     style.colors[StyleColor::TitleBgActive as usize] = style.colors[StyleColor::TitleBg as usize];
@@ -128,7 +133,11 @@ fn main() {
 
 fn check_for_thread_messages(program_state: &mut ProgramState) {
     check_read_write_threads_messages(program_state);
-    check_autosave_thread_messages(program_state);
+    check_autosave(program_state);
+    check_season_threads_messages(program_state);
+    check_team_import_threads_messages(program_state);
+    check_server_messages(program_state);
+    program_state.competition.export_jobs.check_messages();
 }
 
 // TODO: Remove for productive builds
@@ -166,6 +175,7 @@ fn initial_state(state: &mut ProgramState) {
                         //Some(String::from("Mustername A.5")),
                         //Some(String::from("Mustername A.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam B"),
@@ -178,6 +188,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername B.5")),
                         Some(String::from("Mustername B.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam C"),
@@ -190,6 +201,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername C.5")),
                         Some(String::from("Mustername C.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam D"),
@@ -202,6 +214,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername D.5")),
                         Some(String::from("Mustername D.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam E"),
@@ -214,6 +227,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername E.5")),
                         Some(String::from("Mustername E.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam F"),
@@ -226,6 +240,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername F.5")),
                         Some(String::from("Mustername F.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam G"),
@@ -238,6 +253,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername G.5")),
                         Some(String::from("Mustername G.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam H"),
@@ -250,6 +266,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername H.5")),
                         Some(String::from("Mustername H.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam I"),
@@ -262,6 +279,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername I.5")),
                         Some(String::from("Mustername I.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam J"),
@@ -274,6 +292,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername J.5")),
                         Some(String::from("Mustername J.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 /*Team {
                     name: String::from("Musterteam K"),
@@ -300,6 +319,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername N.5")),
                         Some(String::from("Mustername N.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam O"),
@@ -312,6 +332,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername O.5")),
                         Some(String::from("Mustername O.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam P"),
@@ -324,6 +345,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername P.5")),
                         Some(String::from("Mustername P.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam Q"),
@@ -336,6 +358,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername Q.5")),
                         Some(String::from("Mustername Q.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam R"),
@@ -348,6 +371,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername R.5")),
                         Some(String::from("Mustername R.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam S"),
@@ -360,6 +384,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername S.5")),
                         Some(String::from("Mustername S.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam T"),
@@ -372,6 +397,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername T.5")),
                         Some(String::from("Mustername T.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam U"),
@@ -384,6 +410,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername U.5")),
                         Some(String::from("Mustername U.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam V"),
@@ -396,6 +423,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername V.5")),
                         Some(String::from("Mustername V.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 Team {
                     name: String::from("Musterteam W"),
@@ -408,6 +436,7 @@ fn initial_state(state: &mut ProgramState) {
                         Some(String::from("Mustername W.5")),
                         Some(String::from("Mustername W.6")),
                     ],
+                    license_number: String::from(""),
                 },
                 /*Team {
                     name: String::from("Musterteam X"),
@@ -430,6 +459,9 @@ fn initial_state(state: &mut ProgramState) {
         matches: vec![],
         current_batch: vec![1, 0],
         with_break: true,
+        region_scoring: RegionScoring::PlacementPoints,
+        start_time: String::from("09:00"),
+        batch_duration_minutes: 30,
     });
     state.new_screen_state = None;
     state.erg_screen_state = Some(ErgScreenState::new(2));
@@ -519,6 +551,10 @@ pub struct ProgramState {
     pub button_state: ButtonState,
     pub main_menu_bar_state: MainMenuBarState,
     pub threads: ThreadState,
+    pub settings: Settings,
+    pub autosave_state: AutosaveState,
+    pub server_state: ServerState,
+    pub beamer_state: BeamerState,
 }
 
 impl ProgramState {
@@ -532,6 +568,10 @@ impl ProgramState {
             button_state: ButtonState::empty(),
             main_menu_bar_state: MainMenuBarState::empty(),
             threads: ThreadState::new(),
+            settings: Settings::load(),
+            autosave_state: AutosaveState::new(),
+            server_state: ServerState::new(),
+            beamer_state: BeamerState::new(),
         }
     }
 
@@ -572,9 +612,9 @@ impl ProgramState {
 pub struct ThreadState {
     pub save_channels: Vec<Receiver<Result<Option<PathBuf>, Error>>>,
     pub open_channels: Vec<Receiver<Result<Option<PathBuf>, Error>>>,
-    pub autosave_channel: Option<Receiver<()>>,
-    pub autosave_guard: Option<Guard>,
-    pub timer: Option<Timer>,
+    pub season_add_channels: Vec<Receiver<Result<Option<PathBuf>, Error>>>,
+    pub season_export_channels: Vec<Receiver<Result<Option<PathBuf>, Error>>>,
+    pub team_import_channels: Vec<Receiver<Result<Option<PathBuf>, Error>>>,
 }
 
 impl ThreadState {
@@ -582,9 +622,9 @@ impl ThreadState {
         Self {
             save_channels: Vec::new(),
             open_channels: Vec::new(),
-            autosave_channel: None,
-            autosave_guard: None,
-            timer: None,
+            season_add_channels: Vec::new(),
+            season_export_channels: Vec::new(),
+            team_import_channels: Vec::new(),
         }
     }
 }
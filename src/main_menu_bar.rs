@@ -1,6 +1,20 @@
-use imgui::{Condition, MenuItem, Ui, Window};
+use std::path::PathBuf;
 
-use crate::{common::center, screens::buttons, ProgramState};
+use imgui::{ChildWindow, Condition, MenuItem, Selectable, Slider, TableFlags, Ui, Window};
+
+use crate::{
+    common::center,
+    data::{
+        autosave::{self, RecoverableAutosave},
+        export_jobs::{open_file, ExportJobStatus},
+        journal::{read_journal, replay_history, JournalEntry},
+        read_write::list_backups,
+        season::{self, SeasonCarryOver},
+        templates,
+    },
+    screens::buttons,
+    server, ProgramStage, ProgramState,
+};
 
 pub fn draw_main_menu_bar(ui: &Ui, program_state: &mut ProgramState) {
     if let Some(_) = ui.begin_main_menu_bar() {
@@ -17,6 +31,139 @@ pub fn draw_main_menu_bar(ui: &Ui, program_state: &mut ProgramState) {
             if MenuItem::new("Save as").build(ui) {
                 buttons::save_as_action(program_state);
             }
+            if MenuItem::new("Restore backup")
+                .enabled(program_state.competition.absolute_file_path.is_some())
+                .build(ui)
+            {
+                let menu_bar_state = &mut program_state.main_menu_bar_state;
+                menu_bar_state.restore_backup_list = list_backups(
+                    program_state
+                        .competition
+                        .absolute_file_path
+                        .as_ref()
+                        .unwrap(),
+                );
+                menu_bar_state.restore_backup_err_msg = None;
+                menu_bar_state.restore_backup_popup = true;
+            }
+            if MenuItem::new("Journal history")
+                .enabled(program_state.competition.absolute_file_path.is_some())
+                .build(ui)
+            {
+                let menu_bar_state = &mut program_state.main_menu_bar_state;
+                match read_journal(
+                    program_state
+                        .competition
+                        .absolute_file_path
+                        .as_ref()
+                        .unwrap(),
+                ) {
+                    Ok(entries) => {
+                        menu_bar_state.journal_step = entries.len() as u32;
+                        menu_bar_state.journal_entries = entries;
+                        menu_bar_state.journal_err_msg = None;
+                    }
+                    Err(msg) => {
+                        menu_bar_state.journal_entries = vec![];
+                        menu_bar_state.journal_err_msg = Some(msg);
+                    }
+                }
+                menu_bar_state.journal_popup = true;
+            }
+            if MenuItem::new("Exports").build(ui) {
+                program_state.main_menu_bar_state.export_jobs_popup = true;
+            }
+            if MenuItem::new("Recover autosaves").build(ui) {
+                autosave::check_for_recoverable_autosaves(program_state);
+                program_state.main_menu_bar_state.recovery_popup = true;
+            }
+            if MenuItem::new("Settings").build(ui) {
+                program_state.main_menu_bar_state.settings_err_msg = None;
+                program_state.main_menu_bar_state.settings_popup = true;
+            }
+        }
+
+        if let Some(_) = ui.begin_menu("Season") {
+            let has_competition = program_state.competition.data.is_some();
+            if MenuItem::new("Add competition to season")
+                .enabled(has_competition)
+                .build(ui)
+            {
+                season::add_to_season_action(program_state);
+            }
+            if MenuItem::new("Export season table")
+                .enabled(has_competition)
+                .build(ui)
+            {
+                season::export_season_table_action(program_state);
+            }
+
+            // the carry-over is stored in the season file when a competition is added
+            if let Some(_) = ui.begin_menu("Point carry-over") {
+                SeasonCarryOver::all().iter().for_each(|carry_over| {
+                    if MenuItem::new(carry_over.to_string())
+                        .selected(
+                            program_state.main_menu_bar_state.season_carry_over == *carry_over,
+                        )
+                        .build(ui)
+                    {
+                        program_state.main_menu_bar_state.season_carry_over = *carry_over;
+                    }
+                });
+            }
+        }
+
+        if let Some(_) = ui.begin_menu("Server") {
+            let running = program_state.server_state.server.is_some();
+            if MenuItem::new("Start live results")
+                .enabled(!running)
+                .build(ui)
+            {
+                server::start_server_action(program_state);
+            }
+            if MenuItem::new("Stop live results")
+                .enabled(running)
+                .build(ui)
+            {
+                server::stop_server_action(program_state);
+            }
+
+            let server_state = &program_state.server_state;
+            if let Some(server) = server_state.server.as_ref() {
+                ui.separator();
+                ui.text(format!("Live results at {}", server.url));
+                ui.text(format!(
+                    "Result entry at {}entry with PIN {}",
+                    server.url, server.pin
+                ));
+            } else if let Some(msg) = server_state.err_msg.as_ref() {
+                ui.separator();
+                ui.text_disabled(msg);
+            }
+        }
+
+        if let Some(_) = ui.begin_menu("Display") {
+            let beamer_state = &mut program_state.beamer_state;
+            if MenuItem::new("Open public display")
+                .enabled(!beamer_state.open)
+                .build(ui)
+            {
+                beamer_state.open = true;
+            }
+            if MenuItem::new("Close public display")
+                .enabled(beamer_state.open)
+                .build(ui)
+            {
+                beamer_state.open = false;
+            }
+            if MenuItem::new("Announcements").build(ui) {
+                program_state.main_menu_bar_state.announcements_popup = true;
+            }
+
+            if let Some(msg) = program_state.beamer_state.err_msg.as_ref() {
+                ui.separator();
+                ui.text_disabled(msg);
+            }
         }
 
         if let Some(_) = ui.begin_menu("Help") {
@@ -24,9 +171,510 @@ pub fn draw_main_menu_bar(ui: &Ui, program_state: &mut ProgramState) {
                 program_state.main_menu_bar_state.about_popup = true;
             }
         }
+
+        draw_autosave_status(ui, program_state);
     }
 
     about_window(ui, program_state);
+    restore_backup_window(ui, program_state);
+    recovery_window(ui, program_state);
+    journal_window(ui, program_state);
+    settings_window(ui, program_state);
+    announcements_window(ui, program_state);
+    export_jobs_window(ui, program_state);
+}
+
+// draws the time of the last autosave right aligned in the menu bar
+fn draw_autosave_status(ui: &Ui, program_state: &ProgramState) {
+    let autosave_state = &program_state.autosave_state;
+    let status = if let Some(msg) = autosave_state.last_error.as_ref() {
+        format!("Autosave failed: {msg}")
+    } else if let Some(last_autosave) = autosave_state.last_autosave.as_ref() {
+        format!("Last autosave: {}", last_autosave.format("%H:%M:%S"))
+    } else if program_state.settings.autosave_interval_minutes == 0 {
+        String::from("Autosave disabled")
+    } else {
+        return;
+    };
+
+    ui.set_cursor_pos([
+        ui.window_size()[0] - ui.calc_text_size(&status)[0] - 20.0,
+        ui.cursor_pos()[1],
+    ]);
+    ui.text_disabled(status);
+}
+
+fn restore_backup_window(ui: &Ui, program_state: &mut ProgramState) {
+    if !program_state.main_menu_bar_state.restore_backup_popup {
+        return;
+    }
+
+    let mut restore_path = None;
+    let menu_bar_state = &mut program_state.main_menu_bar_state;
+    Window::new("Restore backup")
+        .collapsible(false)
+        .opened(&mut menu_bar_state.restore_backup_popup)
+        .position(
+            [
+                program_state.size[0] * (0.5 - 0.25),
+                program_state.size[1] * (0.5 - 0.25),
+            ],
+            Condition::Appearing,
+        )
+        .size(
+            [program_state.size[0] * 0.5, program_state.size[1] * 0.5],
+            Condition::Appearing,
+        )
+        .build(ui, || {
+            if menu_bar_state.restore_backup_list.is_empty() {
+                ui.text("There are no backups of this competition.");
+                return;
+            }
+
+            ui.text("Restoring a backup replaces the current competition data.");
+            ui.text("The data is written to the competition file with the next save.");
+            ui.separator();
+
+            // list the backups, the newest first
+            menu_bar_state
+                .restore_backup_list
+                .iter()
+                .for_each(|backup_path| {
+                    let file_name = backup_path.file_name().unwrap().to_string_lossy();
+                    if ui.button(format!("Restore##{file_name}")) {
+                        restore_path = Some(backup_path.clone());
+                    }
+                    ui.same_line();
+                    ui.text(&file_name);
+                });
+
+            if let Some(msg) = menu_bar_state.restore_backup_err_msg.as_ref() {
+                ui.separator();
+                ui.text(msg);
+            }
+        });
+
+    if let Some(backup_path) = restore_path {
+        match program_state.competition.handle_restore_backup(backup_path) {
+            Ok(_) => {
+                program_state.main_menu_bar_state.restore_backup_popup = false;
+                // the group count may differ, therefore reset the erg screen completely
+                program_state.erg_screen_state = None;
+                program_state.switch_to_stage(ProgramStage::CurrentErgViewStage);
+            }
+            Err(msg) => program_state.main_menu_bar_state.restore_backup_err_msg = Some(msg),
+        }
+    }
+}
+
+fn recovery_window(ui: &Ui, program_state: &mut ProgramState) {
+    if !program_state.main_menu_bar_state.recovery_popup {
+        return;
+    }
+
+    let mut restore_idx = None;
+    let mut discard_idx = None;
+    let menu_bar_state = &mut program_state.main_menu_bar_state;
+    Window::new("Recover autosaves")
+        .collapsible(false)
+        .opened(&mut menu_bar_state.recovery_popup)
+        .position(
+            [
+                program_state.size[0] * (0.5 - 0.3),
+                program_state.size[1] * (0.5 - 0.3),
+            ],
+            Condition::Appearing,
+        )
+        .size(
+            [program_state.size[0] * 0.6, program_state.size[1] * 0.6],
+            Condition::Appearing,
+        )
+        .build(ui, || {
+            if menu_bar_state.recovery_list.is_empty() {
+                ui.text("There are no autosaves newer than the saved competitions.");
+                return;
+            }
+
+            ui.text("ISRAT was not closed properly. These autosaves are newer than the saved competitions.");
+            ui.text("Restoring an autosave replaces the current competition data.");
+            ui.separator();
+
+            menu_bar_state
+                .recovery_list
+                .iter()
+                .enumerate()
+                .for_each(|(idx, autosave)| {
+                    draw_recoverable_autosave(ui, autosave);
+                    if ui.button(format!("Restore##recover{idx}")) {
+                        restore_idx = Some(idx);
+                    }
+                    ui.same_line();
+                    if ui.button(format!("Discard##recover{idx}")) {
+                        discard_idx = Some(idx);
+                    }
+                    ui.separator();
+                });
+        });
+
+    if let Some(idx) = discard_idx {
+        let autosave = program_state.main_menu_bar_state.recovery_list.remove(idx);
+        autosave::discard_autosave(&autosave);
+    }
+
+    if let Some(idx) = restore_idx {
+        let autosave = program_state.main_menu_bar_state.recovery_list.remove(idx);
+        program_state.main_menu_bar_state.recovery_popup = false;
+        autosave::restore_autosave(program_state, autosave);
+    }
+}
+
+// draws the origin of the autosave and a table comparing it to the competition file
+fn draw_recoverable_autosave(ui: &Ui, autosave: &RecoverableAutosave) {
+    ui.text(format!(
+        "Autosave of {}",
+        autosave.autosave_time.format("%d.%m.%Y %H:%M:%S")
+    ));
+    match autosave.source_file.as_ref() {
+        Some(source_file) if autosave.saved_data.is_some() => {
+            ui.text(format!("Competition file: {}", source_file.display()))
+        }
+        Some(source_file) => ui.text(format!(
+            "Competition file: {} (missing or unreadable)",
+            source_file.display()
+        )),
+        None => ui.text("The competition was never saved."),
+    }
+
+    let table_id = format!("##recovery_table_{}", autosave.autosave_path.display());
+    if let Some(_table_token) = ui.begin_table_with_flags(
+        table_id,
+        3,
+        TableFlags::ROW_BG | TableFlags::BORDERS | TableFlags::SIZING_STRETCH_SAME,
+    ) {
+        ui.table_setup_column("");
+        ui.table_setup_column("Autosave");
+        ui.table_setup_column("Saved file");
+        ui.table_headers_row();
+
+        autosave
+            .preview_rows()
+            .iter()
+            .for_each(|(label, autosave_value, saved_value)| {
+                ui.table_next_row();
+                ui.table_next_column();
+                ui.text(label);
+                ui.table_next_column();
+                ui.text(autosave_value);
+                ui.table_next_column();
+                // highlight the values which differ from the competition file
+                if autosave_value != saved_value {
+                    ui.text_colored([1.0, 0.6, 0.0, 1.0], saved_value);
+                } else {
+                    ui.text(saved_value);
+                }
+            });
+    }
+}
+
+fn journal_window(ui: &Ui, program_state: &mut ProgramState) {
+    if !program_state.main_menu_bar_state.journal_popup {
+        return;
+    }
+
+    let mut restore_state = None;
+    let menu_bar_state = &mut program_state.main_menu_bar_state;
+    Window::new("Journal history")
+        .collapsible(false)
+        .opened(&mut menu_bar_state.journal_popup)
+        .position(
+            [
+                program_state.size[0] * (0.5 - 0.3),
+                program_state.size[1] * (0.5 - 0.3),
+            ],
+            Condition::Appearing,
+        )
+        .size(
+            [program_state.size[0] * 0.6, program_state.size[1] * 0.6],
+            Condition::Appearing,
+        )
+        .build(ui, || {
+            if let Some(msg) = menu_bar_state.journal_err_msg.as_ref() {
+                ui.text(msg);
+            }
+            if menu_bar_state.journal_entries.is_empty() {
+                ui.text("There are no journal entries for this competition.");
+                return;
+            }
+
+            let entry_count = menu_bar_state.journal_entries.len() as u32;
+            Slider::new("Step", 1, entry_count).build(ui, &mut menu_bar_state.journal_step);
+
+            // list all changes, the changes up to the selected step are replayed
+            ChildWindow::new("##journal_entries")
+                .size([0.0, ui.content_region_avail()[1] * 0.6])
+                .border(true)
+                .build(ui, || {
+                    menu_bar_state
+                        .journal_entries
+                        .iter()
+                        .enumerate()
+                        .for_each(|(idx, entry)| {
+                            let step = idx as u32 + 1;
+                            if Selectable::new(format!("{step}. {} - {}", entry.time, entry.event))
+                                .selected(step == menu_bar_state.journal_step)
+                                .build(ui)
+                            {
+                                menu_bar_state.journal_step = step;
+                            }
+                        });
+                });
+
+            ui.separator();
+            match replay_history(
+                &menu_bar_state.journal_entries,
+                menu_bar_state.journal_step as usize,
+            ) {
+                Ok(data) => {
+                    ui.text(format!("State after step {}:", menu_bar_state.journal_step));
+                    (0..data.current_batch.len()).for_each(|group_idx| {
+                        let group_name = match data.group_names.as_ref() {
+                            Some(group_names) if group_idx < group_names.len() => {
+                                group_names[group_idx].clone()
+                            }
+                            _ => format!("Group {}", group_idx + 1),
+                        };
+                        ui.bullet_text(format!(
+                            "{group_name}: batch {}",
+                            data.current_batch[group_idx] + 1
+                        ));
+                    });
+
+                    if ui.button("Restore this state") {
+                        restore_state = Some(data);
+                    }
+                }
+                Err(msg) => ui.text(msg),
+            }
+        });
+
+    if let Some(data) = restore_state {
+        match program_state.competition.handle_restore_state(data) {
+            Ok(_) => {
+                program_state.main_menu_bar_state.journal_popup = false;
+                // the group count may differ, therefore reset the erg screen completely
+                program_state.erg_screen_state = None;
+                program_state.switch_to_stage(ProgramStage::CurrentErgViewStage);
+            }
+            Err(msg) => program_state.main_menu_bar_state.journal_err_msg = Some(msg),
+        }
+    }
+}
+
+fn settings_window(ui: &Ui, program_state: &mut ProgramState) {
+    if !program_state.main_menu_bar_state.settings_popup {
+        return;
+    }
+
+    let menu_bar_state = &mut program_state.main_menu_bar_state;
+    let settings = &mut program_state.settings;
+    let competition = &mut program_state.competition;
+    Window::new("Settings")
+        .collapsible(false)
+        .opened(&mut menu_bar_state.settings_popup)
+        .position(
+            [
+                program_state.size[0] * (0.5 - 0.25),
+                program_state.size[1] * (0.5 - 0.25),
+            ],
+            Condition::Appearing,
+        )
+        .size(
+            [program_state.size[0] * 0.5, program_state.size[1] * 0.5],
+            Condition::Appearing,
+        )
+        .build(ui, || {
+            let mut backup_count = settings.backup_count as i32;
+            ui.input_int("Backups kept per competition file", &mut backup_count)
+                .build();
+            settings.backup_count = backup_count.max(0) as u32;
+
+            let mut autosave_interval = settings.autosave_interval_minutes as i32;
+            ui.input_int(
+                "Autosave interval in minutes (0 = off)",
+                &mut autosave_interval,
+            )
+            .build();
+            settings.autosave_interval_minutes = autosave_interval.max(0) as u32;
+
+            let mut retention_days = settings.autosave_retention_days as i32;
+            ui.input_int("Keep autosaves for days (0 = forever)", &mut retention_days)
+                .build();
+            settings.autosave_retention_days = retention_days.max(0) as u32;
+
+            let mut server_port = settings.server_port as i32;
+            ui.input_int("Port of the live results server", &mut server_port)
+                .build();
+            settings.server_port = server_port.clamp(1, u16::MAX as i32) as u16;
+
+            let mut beamer_interval = settings.beamer_interval_seconds as i32;
+            ui.input_int(
+                "Seconds per view of the public display",
+                &mut beamer_interval,
+            )
+            .build();
+            settings.beamer_interval_seconds = beamer_interval.max(1) as u32;
+
+            // the TeX resources of the PDF exports, halls often have no internet
+            ui.input_text(
+                "Local TeX bundle (zip file or directory, empty = online)",
+                &mut settings.tex_bundle_path,
+            )
+            .build();
+            ui.checkbox(
+                "Offline: use only the prepared TeX resources",
+                &mut settings.tex_offline,
+            );
+            if ui.button("Prepare offline exports") {
+                menu_bar_state.settings_err_msg =
+                    match competition.prepare_offline_exports(settings.tex_bundle()) {
+                        Ok(_) => Some(String::from("Preparing, see File > Exports.")),
+                        Err(msg) => Some(msg),
+                    };
+            }
+
+            ui.separator();
+            if ui.button("Save settings") {
+                menu_bar_state.settings_err_msg = match settings.save() {
+                    Ok(_) => Some(String::from("Settings saved.")),
+                    Err(msg) => Some(msg),
+                };
+            }
+
+            // the copied templates override the built-in LaTeX templates of the PDF exports
+            ui.same_line();
+            if ui.button("Copy templates for editing") {
+                menu_bar_state.settings_err_msg = match templates::write_default_templates() {
+                    Ok(template_dir) => Some(format!("Templates in {}", template_dir.display())),
+                    Err(msg) => Some(msg),
+                };
+            }
+
+            if let Some(msg) = menu_bar_state.settings_err_msg.as_ref() {
+                ui.same_line();
+                ui.text(msg);
+            }
+        });
+}
+
+// lists the PDF exports with their progress, the window opens when an export is started
+fn export_jobs_window(ui: &Ui, program_state: &mut ProgramState) {
+    let export_jobs = &mut program_state.competition.export_jobs;
+    let menu_bar_state = &mut program_state.main_menu_bar_state;
+    if export_jobs.take_started() {
+        menu_bar_state.export_jobs_popup = true;
+        menu_bar_state.export_jobs_err_msg = None;
+    }
+    if !menu_bar_state.export_jobs_popup {
+        return;
+    }
+
+    Window::new("Exports")
+        .collapsible(false)
+        .opened(&mut menu_bar_state.export_jobs_popup)
+        .position(
+            [
+                program_state.size[0] * (0.5 - 0.25),
+                program_state.size[1] * (0.5 - 0.25),
+            ],
+            Condition::Appearing,
+        )
+        .size(
+            [program_state.size[0] * 0.5, program_state.size[1] * 0.5],
+            Condition::Appearing,
+        )
+        .build(ui, || {
+            if export_jobs.jobs.is_empty() {
+                ui.text("There are no exports.");
+                return;
+            }
+
+            if let Some(_table_token) =
+                ui.begin_table_with_flags("##export_jobs", 3, TableFlags::ROW_BG)
+            {
+                export_jobs
+                    .jobs
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(idx, job)| {
+                        ui.table_next_row();
+                        ui.table_next_column();
+                        ui.text(&job.name);
+
+                        ui.table_next_column();
+                        let mut open_path = None;
+                        match &job.status {
+                            ExportJobStatus::Running(step) => ui.text(format!("{step} ...")),
+                            ExportJobStatus::Finished(path_opt) => {
+                                ui.text("Finished");
+                                open_path = path_opt.clone();
+                            }
+                            ExportJobStatus::Failed(msg) => {
+                                ui.text_colored([1.0, 0.0, 0.0, 1.0], msg)
+                            }
+                            ExportJobStatus::Cancelled => ui.text("Cancelled"),
+                        }
+
+                        ui.table_next_column();
+                        if job.is_running() && ui.button(format!("Cancel##export{idx}")) {
+                            job.cancel();
+                        }
+                        if let Some(path) = open_path {
+                            if ui.button(format!("Open file##export{idx}")) {
+                                menu_bar_state.export_jobs_err_msg = open_file(&path).err();
+                            }
+                        }
+                    });
+            }
+
+            ui.separator();
+            if ui.button("Clear finished") {
+                export_jobs.clear_finished();
+            }
+
+            if let Some(msg) = menu_bar_state.export_jobs_err_msg.as_ref() {
+                ui.same_line();
+                ui.text(msg);
+            }
+        });
+}
+
+// the announcements are shown on the public display, one announcement per line
+fn announcements_window(ui: &Ui, program_state: &mut ProgramState) {
+    if !program_state.main_menu_bar_state.announcements_popup {
+        return;
+    }
+
+    let announcements = &mut program_state.beamer_state.announcements;
+    Window::new("Announcements")
+        .collapsible(false)
+        .opened(&mut program_state.main_menu_bar_state.announcements_popup)
+        .position(
+            [
+                program_state.size[0] * (0.5 - 0.25),
+                program_state.size[1] * (0.5 - 0.25),
+            ],
+            Condition::Appearing,
+        )
+        .size(
+            [program_state.size[0] * 0.5, program_state.size[1] * 0.5],
+            Condition::Appearing,
+        )
+        .build(ui, || {
+            ui.text("One announcement per line, the view is left out if there are none.");
+            let size = ui.content_region_avail();
+            ui.input_text_multiline("##announcements", announcements, size)
+                .build();
+        });
 }
 
 fn about_window(ui: &Ui, program_state: &mut ProgramState) {
@@ -67,10 +715,42 @@ fn about_window(ui: &Ui, program_state: &mut ProgramState) {
 
 pub struct MainMenuBarState {
     pub about_popup: bool,
+    pub season_carry_over: SeasonCarryOver, // the carry-over written to the season file when adding a competition
+    pub restore_backup_popup: bool,
+    pub restore_backup_list: Vec<PathBuf>, // the backups of the current competition file, the newest first
+    pub restore_backup_err_msg: Option<String>,
+    pub settings_popup: bool,
+    pub settings_err_msg: Option<String>, // the result of the last try to save the settings
+    pub announcements_popup: bool,
+    pub export_jobs_popup: bool,
+    pub export_jobs_err_msg: Option<String>, // the reason why a finished export could not be opened
+    pub recovery_popup: bool,
+    pub recovery_list: Vec<RecoverableAutosave>, // the autosaves newer than their competition file, the newest first
+    pub journal_popup: bool,
+    pub journal_entries: Vec<JournalEntry>, // the journal of the current competition file, the oldest first
+    pub journal_step: u32, // the number of journal entries replayed in the history window
+    pub journal_err_msg: Option<String>,
 }
 
 impl MainMenuBarState {
     pub fn empty() -> Self {
-        Self { about_popup: false }
+        Self {
+            about_popup: false,
+            season_carry_over: SeasonCarryOver::default(),
+            restore_backup_popup: false,
+            restore_backup_list: vec![],
+            restore_backup_err_msg: None,
+            settings_popup: false,
+            settings_err_msg: None,
+            announcements_popup: false,
+            export_jobs_popup: false,
+            export_jobs_err_msg: None,
+            recovery_popup: false,
+            recovery_list: vec![],
+            journal_popup: false,
+            journal_entries: vec![],
+            journal_step: 0,
+            journal_err_msg: None,
+        }
     }
 }
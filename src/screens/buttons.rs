@@ -42,7 +42,7 @@ pub fn save_action(program_state: &mut ProgramState) {
         // save to file if file_name is known, e.g. if file was opened or previously saved to this file_path
         let export_res = program_state
             .competition
-            .handle_save_file(file_path.to_path_buf());
+            .handle_save_file(file_path.to_path_buf(), program_state.settings.backup_count);
 
         match export_res {
             Ok(_) => (),
@@ -74,7 +74,12 @@ pub fn save_as_action(program_state: &mut ProgramState) {
     thread::Builder::new()
         .name(String::from("Save as dialog thread"))
         .spawn(move || {
-            let mut dialog = FileDialog::new();
+            let mut dialog = FileDialog::new()
+                .add_filter("ISRAT Data Files", &["json"])
+                .add_filter("ISRAT Archives (data, exports & logos)", &["isra"]);
+            if cfg!(feature = "sqlite") {
+                dialog = dialog.add_filter("ISRAT Databases", &["sqlite"]);
+            }
 
             let filename_suggestion = if let Some(ref path) = absoulte_file_path {
                 path.file_name().unwrap().to_str().unwrap().to_string()
@@ -107,8 +112,11 @@ pub fn open_action(program_state: &mut ProgramState) {
     thread::Builder::new()
         .name(String::from("Open dialog thread"))
         .spawn(move || {
-            let mut open_dialog =
-                FileDialog::new().add_filter("ISRAT Data Files", &["json", "isra"]);
+            let mut open_dialog = if cfg!(feature = "sqlite") {
+                FileDialog::new().add_filter("ISRAT Data Files", &["json", "isra", "sqlite"])
+            } else {
+                FileDialog::new().add_filter("ISRAT Data Files", &["json", "isra"])
+            };
 
             println!(
                 "{}",
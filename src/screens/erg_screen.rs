@@ -1,11 +1,15 @@
 use imgui::{
-    ChildWindow, Id, Key, StyleColor, TableColumnFlags, TableColumnSetup, TableFlags,
-    TableRowFlags, Ui,
+    ChildWindow, CollapsingHeader, Id, Key, Selectable, StyleColor, TabItemFlags, TableColumnFlags,
+    TableColumnSetup, TableFlags, TableRowFlags, Ui,
 };
 
 use crate::{
     common::center,
-    data::{CompetitionData, InterimResultEntry, Match, MatchResult, Team},
+    data::{
+        journal::{JournalEvent, LaneResult},
+        parse_match_id, season, CompetitionData, InterimResultEntry, Match, MatchResult,
+        RegionScoring, Team,
+    },
     screens::buttons,
     ProgramStage, ProgramState,
 };
@@ -33,10 +37,25 @@ pub fn build(ui: &Ui, program_state: &mut ProgramState, menu_bar_height: f32) {
             }
 
             let data = program_state.competition.data.as_mut().unwrap();
+            let mut journal_events = vec![]; // the changes of this frame, which are written to the journal
 
             if let Some(_tab_bar_token) = ui.tab_bar("##group_selector") {
                 for (idx, group_name) in data.group_names.as_ref().unwrap().iter().enumerate() {
-                    if let Some(_tab_item_token) = ui.tab_item(group_name) {
+                    // a scanned lane card selects the tab of its group
+                    let tab_item_flags = if program_state
+                        .erg_screen_state
+                        .as_ref()
+                        .unwrap()
+                        .select_group
+                        == Some(idx)
+                    {
+                        TabItemFlags::SET_SELECTED
+                    } else {
+                        TabItemFlags::empty()
+                    };
+                    if let Some(_tab_item_token) =
+                        ui.tab_item_with_flags(group_name, None, tab_item_flags)
+                    {
                         let erg_screen_state = program_state.erg_screen_state.as_mut().unwrap();
 
                         // calculate interim result if not available
@@ -68,16 +87,45 @@ pub fn build(ui: &Ui, program_state: &mut ProgramState, menu_bar_height: f32) {
                             data.team_distribution[1] / 2,
                         );
 
-                        draw_submit_button(
+                        journal_events.extend(draw_submit_button(
                             ui,
                             erg_screen_state,
                             &mut program_state.competition.current_interim_result[idx],
-                            &mut data.matches[idx],
-                            &mut data.current_batch[idx],
+                            data,
                             idx,
-                        );
+                        ));
+
+                        journal_events.extend(draw_correction(
+                            ui,
+                            erg_screen_state,
+                            &mut program_state.competition.current_interim_result[idx],
+                            data,
+                            idx,
+                        ));
                     }
                 }
+
+                program_state
+                    .erg_screen_state
+                    .as_mut()
+                    .unwrap()
+                    .select_group = None;
+
+                if let Some(_tab_item_token) = ui.tab_item("Regions") {
+                    journal_events.extend(draw_region_table(ui, data));
+                }
+            }
+
+            // write every change to the journal immediately
+            for event in journal_events {
+                // the public display shows the new standings right away
+                if let JournalEvent::BatchSubmitted { group_idx, .. } = &event {
+                    program_state.beamer_state.show_standings(*group_idx);
+                }
+                if let Err(msg) = program_state.competition.record_journal_event(event) {
+                    eprintln!("[erg_screen::build]: {msg}");
+                    program_state.erg_screen_state.as_mut().unwrap().failure_msg = Some(msg);
+                }
             }
         });
 
@@ -113,34 +161,123 @@ pub fn bottom_buttons(ui: &Ui, program_state: &mut ProgramState) {
 
     buttons::open_button(ui, program_state);
 
+    // a keyboard-wedge scanner types the match id of a lane card followed by enter
     let erg_screen_state = program_state.erg_screen_state.as_mut().unwrap();
+    let scan_width_token = ui.push_item_width(ui.calc_text_size("Scan lane card")[0] * 1.5);
+    if ui
+        .input_text("##scan_lane_card", &mut erg_screen_state.scan_input)
+        .hint("Scan lane card")
+        .enter_returns_true(true)
+        .build()
+    {
+        let scanned_id = std::mem::take(&mut erg_screen_state.scan_input);
+        erg_screen_state.failure_msg = erg_screen_state
+            .select_scanned_match(
+                program_state.competition.data.as_ref().unwrap(),
+                &scanned_id,
+            )
+            .err();
+    }
+    scan_width_token.pop(ui);
+
     if erg_screen_state.export_popup {
         if let Some(_token) = ui.begin_popup("##export_popup") {
-            let mut close = false;
+            // the result of the chosen export, the popup is closed after any export
+            let mut export_res = None;
+            let mut export_season = false;
+            let tex_bundle = program_state.settings.tex_bundle();
             if ui.button("Result list") {
-                program_state.competition.export_result_list();
-                close = true;
+                export_res = Some(
+                    program_state
+                        .competition
+                        .export_result_list(tex_bundle.clone()),
+                );
+            }
+
+            if ui.button("Result list (Federation JSON)") {
+                export_res = Some(program_state.competition.export_federation_results());
+            }
+
+            if ui.button("Season table") {
+                // the season file dialog needs the whole program state, it is opened after the popup
+                export_season = true;
+                export_res = Some(Ok(()));
             }
 
             if ui.button("Start list") {
-                program_state.competition.export_start_list();
-                close = true;
+                export_res = Some(
+                    program_state
+                        .competition
+                        .export_start_list(tex_bundle.clone()),
+                );
             }
 
             if ui.button("Team Match Plans") {
-                program_state.competition.export_team_match_plans();
-                close = true;
+                export_res = Some(
+                    program_state
+                        .competition
+                        .export_team_match_plans(tex_bundle.clone()),
+                );
+            }
+
+            if ui.button("Team Calendars (ICS)") {
+                export_res = Some(program_state.competition.export_calendars());
             }
 
             if ui.button("Lane Match Plans") {
-                program_state.competition.export_lane_match_plans();
-                close = true;
+                // the QR codes link to the result entry while the live results server is running
+                let entry_url = program_state
+                    .server_state
+                    .server
+                    .as_ref()
+                    .map(|server| format!("{}entry", server.url));
+                export_res = Some(
+                    program_state
+                        .competition
+                        .export_lane_match_plans(entry_url, tex_bundle.clone()),
+                );
+            }
+
+            if ui.button("Region ranking") {
+                export_res = Some(
+                    program_state
+                        .competition
+                        .export_region_result(tex_bundle.clone()),
+                );
+            }
+
+            ui.separator();
+
+            // raw data for statistics
+            if ui.button("Standings (CSV)") {
+                export_res = Some(program_state.competition.export_standings_csv());
+            }
+
+            if ui.button("Schedule (CSV)") {
+                export_res = Some(program_state.competition.export_schedule_csv());
+            }
+
+            if ui.button("Results (CSV)") {
+                export_res = Some(program_state.competition.export_results_csv());
+            }
+
+            if ui.button("Website (HTML)") {
+                export_res = Some(program_state.competition.export_website());
+            }
+
+            if ui.button("Workbook (XLSX)") {
+                export_res = Some(program_state.competition.export_xlsx());
             }
 
-            if close {
+            if let Some(export_res) = export_res {
+                erg_screen_state.failure_msg = export_res.err();
                 erg_screen_state.export_popup = false;
                 ui.close_current_popup();
             }
+
+            if export_season {
+                season::export_season_table_action(program_state);
+            }
         } else {
             erg_screen_state.export_popup = false;
         }
@@ -253,6 +390,125 @@ fn draw_erg_table(
     }
 }
 
+fn draw_region_table(ui: &Ui, data: &mut CompetitionData) -> Option<JournalEvent> {
+    let mut journal_event = None;
+
+    // draw selector for the scoring used to accumulate the team results
+    ui.text("Scoring:");
+    ui.same_line();
+    if let Some(_combo_token) = ui.begin_combo("##region_scoring", data.region_scoring.to_string())
+    {
+        RegionScoring::all().iter().for_each(|scoring| {
+            if Selectable::new(scoring.to_string())
+                .selected(*scoring == data.region_scoring)
+                .build(ui)
+            {
+                if data.region_scoring != *scoring {
+                    data.region_scoring = *scoring;
+                    journal_event = Some(JournalEvent::RegionScoringChanged {
+                        region_scoring: *scoring,
+                    });
+                }
+            }
+        });
+    }
+
+    ui.new_line();
+
+    let region_result = data.calc_region_result();
+
+    if region_result.is_empty() {
+        center(ui, "No team has a region assigned.");
+        return journal_event;
+    }
+
+    let column_widths = [
+        ui.calc_text_size("Place")[0] * 1.5,
+        0.0,
+        ui.calc_text_size("Teams")[0] * 1.5,
+        ui.calc_text_size("9999")[0] * 2.0,
+        ui.calc_text_size("999 : 999")[0] * 2.0,
+        ui.calc_text_size("99.999")[0] * 2.0,
+        ui.calc_text_size("9999 : 9999")[0] * 2.0,
+    ];
+    let column_names = [
+        "Place",
+        "Region",
+        "Teams",
+        "Points",
+        "Match Points",
+        "Quotient",
+        "Stock Points",
+    ];
+
+    if let Some(_table_token) = ui.begin_table_with_flags(
+        "##region_table",
+        column_names.len(),
+        TableFlags::BORDERS | TableFlags::SIZING_FIXED_FIT,
+    ) {
+        // add the columns, only the region column is stretched
+        column_names
+            .iter()
+            .zip(column_widths.iter())
+            .for_each(|(name, width)| {
+                ui.table_setup_column_with(TableColumnSetup {
+                    name: *name,
+                    flags: if *width == 0.0 {
+                        TableColumnFlags::WIDTH_STRETCH
+                    } else {
+                        TableColumnFlags::WIDTH_FIXED
+                    },
+                    init_width_or_weight: *width,
+                    user_id: Id::Int(0),
+                });
+            });
+
+        // add header row and center the headers
+        ui.table_next_row_with_flags(TableRowFlags::HEADERS);
+        column_names.iter().for_each(|name| {
+            ui.table_next_column();
+            center(ui, name);
+        });
+
+        // draw the rows and center the entries
+        region_result
+            .iter()
+            .enumerate()
+            .for_each(|(place_idx, entry)| {
+                ui.table_next_row();
+
+                ui.table_next_column();
+                center(ui, (place_idx + 1).to_string());
+
+                ui.table_next_column();
+                center(ui, &entry.region);
+
+                ui.table_next_column();
+                center(ui, entry.count_teams.to_string());
+
+                ui.table_next_column();
+                center(ui, entry.points.to_string());
+
+                ui.table_next_column();
+                center(
+                    ui,
+                    format!("{} : {}", entry.match_points[0], entry.match_points[1]),
+                );
+
+                ui.table_next_column();
+                center(ui, format!("{:.3}", entry.quotient));
+
+                ui.table_next_column();
+                center(
+                    ui,
+                    format!("{} : {}", entry.stock_points[0], entry.stock_points[1]),
+                );
+            });
+    }
+
+    journal_event
+}
+
 fn draw_upcoming_matches(
     ui: &Ui,
     matches: &mut Vec<Match>,
@@ -262,7 +518,14 @@ fn draw_upcoming_matches(
     current_batch: u32,
     count_lanes: u32,
 ) {
-    let is_focus_to_move = if ui.is_key_pressed(Key::Tab) || ui.is_key_pressed(Key::Enter) {
+    let is_scanned_match = erg_screen_state.focus_scanned_match
+        && erg_screen_state
+            .selected_field_index
+            .map_or(false, |s_index| s_index[0] == group_idx as u32);
+    let is_focus_to_move = if is_scanned_match {
+        erg_screen_state.focus_scanned_match = false;
+        true
+    } else if ui.is_key_pressed(Key::Tab) || ui.is_key_pressed(Key::Enter) {
         move_focus_for_input(erg_screen_state, group_idx, current_batch, count_lanes);
         true
     } else {
@@ -274,7 +537,7 @@ fn draw_upcoming_matches(
 
     // setup table for upcoming matches and to enter the results
     if let Some(_table_token) =
-        ui.begin_table_with_flags("##upcoming_matches_table", 4, TableFlags::BORDERS)
+        ui.begin_table_with_flags("##upcoming_matches_table", 5, TableFlags::BORDERS)
     {
         // setup up columns
         ui.table_setup_column_with(TableColumnSetup {
@@ -301,6 +564,12 @@ fn draw_upcoming_matches(
             init_width_or_weight: 2.0,
             user_id: Id::Int(0),
         });
+        ui.table_setup_column_with(TableColumnSetup {
+            name: "##Confirm",
+            flags: TableColumnFlags::WIDTH_STRETCH,
+            init_width_or_weight: 1.0,
+            user_id: Id::Int(0),
+        });
 
         // draw upcoming matches for each lane
         (0..count_lanes).for_each(|lane_idx| {
@@ -355,6 +624,8 @@ fn draw_upcoming_matches(
                                 Ok(result) => Some(result),
                                 Err(_) => None,
                             });
+                        // a result edited by the clerk is confirmed
+                        intermediate_result.pending = false;
                     } else {
                         erg_screen_state.intermediate_results[group_idx].push(IntermediateResult {
                             lane_idx,
@@ -364,10 +635,14 @@ fn draw_upcoming_matches(
                                     Err(_) => None,
                                 }
                             }),
+                            pending: false,
                         });
                     }
                 };
 
+                let is_pending = erg_screen_state.intermediate_results[group_idx]
+                    .iter()
+                    .any(|i_res| i_res.lane_idx == lane_idx && i_res.pending);
                 let available_space = ui.content_region_avail()[0];
 
                 let mut draw_input_text = |idx: u32, id: &str| {
@@ -407,6 +682,13 @@ fn draw_upcoming_matches(
                     }
                 };
 
+                // highlight results entered on a tablet until the clerk confirms them
+                let pending_color = if is_pending {
+                    Some(ui.push_style_color(StyleColor::FrameBg, [0.6, 0.4, 0.0, 1.0]))
+                } else {
+                    None
+                };
+
                 draw_input_text(0, "team_a");
 
                 let text_width = ui.calc_text_size(":")[0];
@@ -415,12 +697,28 @@ fn draw_upcoming_matches(
                 ui.same_line_with_pos(available_space * 0.6);
 
                 draw_input_text(1, "team_b");
+
+                if let Some(pending_color) = pending_color {
+                    pending_color.end();
+                }
+
+                ui.table_next_column();
+                if is_pending && ui.button(format!("Confirm##confirm_{lane_idx}")) {
+                    if let Some(intermediate_result) = erg_screen_state.intermediate_results
+                        [group_idx]
+                        .iter_mut()
+                        .find(|i_res| i_res.lane_idx == lane_idx)
+                    {
+                        intermediate_result.pending = false;
+                    }
+                }
             } else {
                 // ... else display that there is no match on this lane
                 center(ui, "Empty");
                 ui.table_next_column();
                 center(ui, "Empty");
                 ui.table_next_column();
+                ui.table_next_column();
             }
         });
     }
@@ -454,57 +752,51 @@ fn draw_submit_button(
     ui: &Ui,
     erg_screen_state: &mut ErgScreenState,
     current_interim_result: &mut Option<Vec<InterimResultEntry>>,
-    matches: &mut Vec<Match>,
-    current_batch: &mut u32,
+    data: &mut CompetitionData,
     group_idx: usize,
-) {
+) -> Option<JournalEvent> {
+    let mut journal_event = None;
+
     // align submit button right, with some indent and draw it
     ui.set_cursor_pos([
         ui.cursor_pos()[0] + ui.content_region_avail()[0] - ui.calc_text_size("Submit")[0] - 20.0,
         ui.cursor_pos()[1],
     ]);
     if ui.button("Submit") {
-        // check for valid inputs
-        erg_screen_state.failure_msg = matches
+        let intermediate_results = &erg_screen_state.intermediate_results[group_idx];
+        if intermediate_results.iter().any(|i_res| i_res.pending) {
+            erg_screen_state.failure_msg = Some(String::from(
+                "Please confirm the results entered on the tablets.",
+            ));
+            return None;
+        }
+
+        // incomplete results are left out, the check of the competition data reports the missing ones
+        let results = intermediate_results
             .iter()
-            .filter(|_match| _match.batch == *current_batch)
-            .map(|_match| {
-                match erg_screen_state.intermediate_results[group_idx]
-                    .iter()
-                    .find(|i_res| i_res.lane_idx == _match.lane)
-                {
-                    Some(i_res) if i_res.result.iter().all(|point_opt| point_opt.is_some()) => None,
-                    _ => Some(String::from("Please enter results for each match played.")),
-                }
+            .filter_map(|i_res| match i_res.result {
+                [Some(points_a), Some(points_b)] => Some(LaneResult {
+                    lane: i_res.lane_idx,
+                    points: [points_a, points_b],
+                }),
+                _ => None,
             })
-            .fold(None, |a, b| if a.is_some() { a } else { b });
-
-        // only submit entered results if input is valid
-        if erg_screen_state.failure_msg.is_some() {
-            return;
-        }
+            .collect();
 
-        // process entered results, i.e. transfer the entered points from the intermediate_results in the state to the matches in the competition data
-        for _match in matches
-            .iter_mut()
-            .filter(|_match| _match.batch == *current_batch)
-        {
-            let i_res = erg_screen_state.intermediate_results[group_idx]
-                .iter()
-                .find(|i_res| i_res.lane_idx == _match.lane)
-                .unwrap();
-
-            _match.points = Some(i_res.result.map(|point_opt| point_opt.unwrap()));
-            _match.result = match _match.points.unwrap()[0].cmp(&_match.points.unwrap()[1]) {
-                std::cmp::Ordering::Less => MatchResult::WinnerB,
-                std::cmp::Ordering::Equal => MatchResult::Draw,
-                std::cmp::Ordering::Greater => MatchResult::WinnerA,
-            };
+        // transfer the entered points from the intermediate_results in the state to the matches in the competition data
+        match data.submit_batch_results(group_idx, data.current_batch[group_idx], results) {
+            Ok(event) => {
+                journal_event = Some(event);
+                erg_screen_state.failure_msg = None;
+            }
+            Err(msg) => {
+                erg_screen_state.failure_msg = Some(msg);
+                return None;
+            }
         }
 
         erg_screen_state.intermediate_results[group_idx].clear();
         *current_interim_result = None;
-        *current_batch += 1;
     }
 
     // display failure message if some exists
@@ -519,6 +811,142 @@ fn draw_submit_button(
         ]);
         ui.text(failure_msg);
     }
+
+    journal_event
+}
+
+// submits results of the current batch received outside of the GUI, e.g. by the REST API, like the submit button does
+pub fn submit_external_results(
+    program_state: &mut ProgramState,
+    group_idx: usize,
+    batch: u32,
+    results: Vec<LaneResult>,
+) -> Result<(), String> {
+    // the clerk decides about the results entered on the tablets or typed in first, they are never discarded silently
+    if let Some(erg_screen_state) = program_state.erg_screen_state.as_ref() {
+        if erg_screen_state.has_unsubmitted_results(group_idx) {
+            return Err(String::from(
+                "The group has unsubmitted results in ISRAT, the clerk has to submit or clear them first.",
+            ));
+        }
+    }
+
+    let event = match program_state.competition.data.as_mut() {
+        Some(data) => data.submit_batch_results(group_idx, batch, results)?,
+        None => return Err(String::from("No competition is loaded!")),
+    };
+
+    program_state.beamer_state.show_standings(group_idx);
+    if let Some(erg_screen_state) = program_state.erg_screen_state.as_mut() {
+        if let Some(intermediate_results) = erg_screen_state.intermediate_results.get_mut(group_idx)
+        {
+            intermediate_results.clear();
+        }
+    }
+    if let Some(interim_result) = program_state
+        .competition
+        .current_interim_result
+        .get_mut(group_idx)
+    {
+        *interim_result = None;
+    }
+
+    // the results are submitted anyway, a failed journal write is only shown to the clerk
+    if let Err(msg) = program_state.competition.record_journal_event(event) {
+        eprintln!("[submit_external_results]: {msg}");
+        if let Some(erg_screen_state) = program_state.erg_screen_state.as_mut() {
+            erg_screen_state.failure_msg = Some(msg);
+        }
+    }
+    Ok(())
+}
+
+// draws the form to correct the result of an already submitted match
+fn draw_correction(
+    ui: &Ui,
+    erg_screen_state: &mut ErgScreenState,
+    current_interim_result: &mut Option<Vec<InterimResultEntry>>,
+    data: &mut CompetitionData,
+    group_idx: usize,
+) -> Option<JournalEvent> {
+    if !CollapsingHeader::new("Correct a submitted result").build(ui) {
+        return None;
+    }
+
+    let teams = &data.teams.as_ref().unwrap()[group_idx];
+    let match_label = |_match: &Match| {
+        format!(
+            "Batch {}, Lane {}: {} vs. {}",
+            _match.batch + 1,
+            _match.lane + 1,
+            teams[_match.team_a].name,
+            teams[_match.team_b].name
+        )
+    };
+
+    // only matches with a submitted result can be corrected
+    let played_matches: Vec<&Match> = data.matches[group_idx]
+        .iter()
+        .filter(|_match| _match.points.is_some() && _match.result != MatchResult::Break)
+        .collect();
+    if played_matches.is_empty() {
+        ui.text("No results have been submitted yet.");
+        return None;
+    }
+
+    let selected_idx = erg_screen_state
+        .correction_match_idx
+        .filter(|idx| *idx < played_matches.len());
+    let preview = match selected_idx {
+        Some(idx) => match_label(played_matches[idx]),
+        None => String::from("Select a match"),
+    };
+    if let Some(_combo_token) = ui.begin_combo(format!("##correction_match{group_idx}"), preview) {
+        played_matches.iter().enumerate().for_each(|(idx, _match)| {
+            if Selectable::new(match_label(_match))
+                .selected(selected_idx == Some(idx))
+                .build(ui)
+            {
+                erg_screen_state.correction_match_idx = Some(idx);
+                erg_screen_state.correction_points = _match.points.unwrap();
+            }
+        });
+    }
+
+    let (batch, lane) = match selected_idx {
+        Some(idx) => (played_matches[idx].batch, played_matches[idx].lane),
+        None => return None,
+    };
+
+    let [points_a, points_b] = &mut erg_screen_state.correction_points;
+    ui.input_int(format!("##correction_points_a{group_idx}"), points_a)
+        .build();
+    ui.same_line();
+    ui.text(":");
+    ui.same_line();
+    ui.input_int(format!("##correction_points_b{group_idx}"), points_b)
+        .build();
+
+    if !ui.button("Apply correction") {
+        return None;
+    }
+
+    let event = JournalEvent::ResultCorrected {
+        group_idx,
+        batch,
+        lane,
+        points: erg_screen_state.correction_points,
+    };
+    match event.apply(data) {
+        Ok(_) => {
+            *current_interim_result = None;
+            Some(event)
+        }
+        Err(msg) => {
+            erg_screen_state.failure_msg = Some(msg);
+            None
+        }
+    }
 }
 
 pub struct ErgScreenState {
@@ -526,6 +954,11 @@ pub struct ErgScreenState {
     failure_msg: Option<String>,
     export_popup: bool,
     selected_field_index: Option<[u32; 4]>, // if not none stores the current focused input text box for the results
+    correction_match_idx: Option<usize>, // the index of the selected match in the list of played matches of the correction form
+    correction_points: [i32; 2],         // the corrected points entered in the correction form
+    scan_input: String,                  // the match id typed by the barcode scanner
+    select_group: Option<usize>,         // the group tab to select in the next frame
+    focus_scanned_match: bool, // whether the input of the scanned match is focused when its group is drawn next
 }
 
 impl ErgScreenState {
@@ -535,11 +968,111 @@ impl ErgScreenState {
             failure_msg: None,
             export_popup: false,
             selected_field_index: None,
+            correction_match_idx: None,
+            correction_points: [0, 0],
+            scan_input: String::new(),
+            select_group: None,
+            focus_scanned_match: false,
         }
     }
+
+    // selects the first result input of a scanned match, only matches of the current batch can be entered
+    fn select_scanned_match(
+        &mut self,
+        data: &CompetitionData,
+        scanned_id: &str,
+    ) -> Result<(), String> {
+        let (group_idx, batch, lane) = match parse_match_id(scanned_id) {
+            Some(ids) => ids,
+            None => return Err(format!("\"{}\" is no match id!", scanned_id.trim())),
+        };
+        let current_batch = match data.current_batch.get(group_idx) {
+            Some(current_batch) => *current_batch,
+            None => return Err(format!("There is no group {}!", group_idx + 1)),
+        };
+        if batch != current_batch {
+            return Err(format!(
+                "The lane card belongs to batch {}, the current batch is {}!",
+                batch + 1,
+                current_batch + 1
+            ));
+        }
+        if !data.matches[group_idx].iter().any(|_match| {
+            _match.batch == batch && _match.lane == lane && _match.result != MatchResult::Break
+        }) {
+            return Err(format!("There is no match on lane {}!", lane + 1));
+        }
+
+        self.select_group = Some(group_idx);
+        self.selected_field_index = Some([group_idx as u32, batch, lane, 0]);
+        self.focus_scanned_match = true;
+        Ok(())
+    }
+
+    // whether results of the current batch were entered on a tablet or typed in, but not submitted yet
+    fn has_unsubmitted_results(&self, group_idx: usize) -> bool {
+        self.intermediate_results
+            .get(group_idx)
+            .map_or(false, |intermediate_results| {
+                intermediate_results.iter().any(|i_res| {
+                    i_res.pending || i_res.result.iter().any(|points| points.is_some())
+                })
+            })
+    }
+
+    // adds a result entered on a tablet for the current batch, it replaces a result entered before
+    pub fn add_pending_result(&mut self, group_idx: usize, lane_idx: u32, points: [i32; 2]) {
+        let intermediate_results = match self.intermediate_results.get_mut(group_idx) {
+            Some(intermediate_results) => intermediate_results,
+            None => return,
+        };
+        intermediate_results.retain(|i_res| i_res.lane_idx != lane_idx);
+        intermediate_results.push(IntermediateResult {
+            result: points.map(Some),
+            lane_idx,
+            pending: true,
+        });
+    }
 }
 
 struct IntermediateResult {
     result: [Option<i32>; 2],
     lane_idx: u32,
+    pending: bool, // entered on a tablet and not yet confirmed by the clerk
+}
+
+#[cfg(test)]
+#[test]
+fn test_submit_external_results() {
+    use crate::{data::two_team_competition, ProgramStage};
+
+    let mut program_state = ProgramState::new(ProgramStage::CurrentErgViewStage, [1280.0, 720.0]);
+    program_state.competition.data = Some(two_team_competition());
+    program_state.erg_screen_state = Some(ErgScreenState::new(1));
+    let results = vec![LaneResult {
+        lane: 0,
+        points: [7, 3],
+    }];
+
+    // a result entered on a tablet and not yet confirmed blocks the submission and is kept
+    program_state
+        .erg_screen_state
+        .as_mut()
+        .unwrap()
+        .add_pending_result(0, 0, [3, 7]);
+    assert!(submit_external_results(&mut program_state, 0, 0, results.clone()).is_err());
+    let data = program_state.competition.data.as_ref().unwrap();
+    assert_eq!(data.current_batch, vec![0]);
+    assert_eq!(data.matches[0][0].points, None);
+    assert!(program_state
+        .erg_screen_state
+        .as_ref()
+        .unwrap()
+        .has_unsubmitted_results(0));
+
+    program_state.erg_screen_state = Some(ErgScreenState::new(1));
+    assert!(submit_external_results(&mut program_state, 0, 0, results).is_ok());
+    let data = program_state.competition.data.as_ref().unwrap();
+    assert_eq!(data.current_batch, vec![1]);
+    assert_eq!(data.matches[0][0].points, Some([7, 3]));
 }
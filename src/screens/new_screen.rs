@@ -1,8 +1,13 @@
-use imgui::{ChildWindow, Selectable, StyleColor, Ui};
+use imgui::{ChildWindow, Condition, Selectable, StyleColor, TableFlags, Ui, Window};
 
 use crate::{
-    data::calc_group_possibilities, screens::buttons, CompetitionData, ProgramStage, ProgramState,
-    Team,
+    data::{
+        calc_group_possibilities,
+        team_import::{import_teams_action, ImportColumn, ImportRowStatus, TeamImport},
+        DEFAULT_BATCH_DURATION_MINUTES,
+    },
+    screens::buttons,
+    CompetitionData, ProgramStage, ProgramState, Team,
 };
 
 use super::my_input_text::{MyMultilineTextInput, MyTextInput};
@@ -61,6 +66,7 @@ pub fn bottom_buttons(ui: &Ui, program_state: &mut ProgramState) {
 // builder for different stages
 
 fn build_init_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height: f32) {
+    let mut stage_committed = false;
     ChildWindow::new("##init_stage_window")
         .size([
             program_state.size[0],
@@ -86,7 +92,9 @@ fn build_init_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                 "Referee:",
                 "Competition Manager:",
                 "Clerk:",
-                "Additional text:"
+                "Additional text:",
+                "Start time:",
+                "Batch duration:"
             ];
 
             let mut my_input_boxes = [
@@ -100,6 +108,11 @@ fn build_init_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                     "Enter the date of the competition",
                     &mut data.date_string,
                 ),
+                MyTextInput::new(
+                    labels[11],
+                    "Enter the planned start of the first batch as HH:MM, leave empty if no times are planned.",
+                    &mut data.start_time,
+                ),
                 MyTextInput::new(
                     labels[2],
                     "Enter the place of the competition",
@@ -220,6 +233,21 @@ fn build_init_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                 ui.checkbox("With Breaks", &mut data.with_break);
             }
 
+            // draw batch duration integer input box, used to plan the start of each batch
+            let mut batch_duration_helper = data.batch_duration_minutes as i32;
+            ui.text(labels[12]);
+            ui.same_line_with_pos(max_label_size + 20.0);
+            {
+                let width_token = ui.push_item_width(text_input_width);
+                ui.input_int("minutes##batch_duration", &mut batch_duration_helper)
+                .build();
+                width_token.pop(ui);
+            }
+            if batch_duration_helper.max(1) as u32 != data.batch_duration_minutes {
+                data.batch_duration_minutes = batch_duration_helper.max(1) as u32;
+                anything_changed = true;
+            }
+
             {
                 let width_token = ui.push_item_width(text_input_width);
                 MyMultilineTextInput::new(labels[10], &mut data.additional_text).build(ui, max_label_size, [text_input_width,ui.current_font_size() * 4.0]);
@@ -237,6 +265,7 @@ fn build_init_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                     new_screen_state.submit_failure_msg = Some(err_msg);
                 } else {
                     new_screen_state.go_to_stage(NewScreenStage::TeamNames, data);
+                    stage_committed = true;
                 }
             }
 
@@ -273,9 +302,15 @@ fn build_init_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                 ui.text(msg);
             }
         });
+
+    if stage_committed {
+        record_setup(program_state);
+    }
 }
 
 fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height: f32) {
+    let mut import_csv = false;
+    let mut stage_committed = false;
     ChildWindow::new("##teams_stage_window")
         .size([
             program_state.size[0],
@@ -305,6 +340,7 @@ fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                                     name: String::from(""),
                                     region: String::from(""),
                                     player_names: [None, None, None, None, None, None],
+                                    license_number: String::from(""),
                                 })
                                 .collect()
                         })
@@ -325,10 +361,14 @@ fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
             let max_label_size = ui.calc_text_size("Group Name")[0]
                 .max(ui.calc_text_size(format!("Team {}", data.team_distribution[1]))[0]);
 
-            let name_text_input_width = program_state.size[0] / 2.0;
+            let name_text_input_width = program_state.size[0] / 2.5;
             let region_label_size = ui.calc_text_size("Region:")[0];
-            let region_text_input_width = program_state.size[0] / 5.0;
+            let region_text_input_width = program_state.size[0] / 6.0;
             let region_label_offset = name_text_input_width + max_label_size + 20.0;
+            let license_label_size = ui.calc_text_size("License:")[0];
+            let license_text_input_width = program_state.size[0] / 8.0;
+            let license_label_offset =
+                region_label_offset + region_label_size + region_text_input_width + 30.0;
 
             // create tab bar for all groups and add text input boxes for setting group and team names
             if let Some(teams) = data.teams.as_mut() {
@@ -380,6 +420,25 @@ fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                                         .build(ui, region_label_size);
                                         item_width_token.pop(ui);
                                     }
+
+                                    ui.same_line();
+
+                                    {
+                                        let item_width_token =
+                                            ui.push_item_width(license_text_input_width);
+                                        MyTextInput::new(
+                                            "License:",
+                                            "Enter the license number of the federation, can be empty.",
+                                            &mut teams_for_group
+                                                .get_mut((team_idx - 1) as usize)
+                                                .unwrap()
+                                                .license_number,
+                                        )
+                                        .offset(license_label_offset)
+                                        .text_input_label(format!("##team_{team_idx}_license"))
+                                        .build(ui, license_label_size);
+                                        item_width_token.pop(ui);
+                                    }
                                 }
                             }
                             item_width_token.pop(ui);
@@ -398,6 +457,7 @@ fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                         data.generate_matches();
                     }
                     new_screen_state.go_to_stage(NewScreenStage::PlayerNames, data);
+                    stage_committed = true;
                 }
             }
 
@@ -413,6 +473,11 @@ fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                 new_screen_state.go_back_popup = true;
             }
 
+            ui.same_line();
+            if ui.button("Import CSV") {
+                import_csv = true;
+            }
+
             if new_screen_state.reset_popup {
                 ui.popup_modal("##reset_popup")
                     .resizable(false)
@@ -458,7 +523,151 @@ fn build_teams_stage(ui: &Ui, program_state: &mut ProgramState, menu_bar_height:
                 ui.same_line();
                 ui.text(msg);
             }
+
+            draw_team_import_window(ui, new_screen_state, data, program_state.size);
+        });
+
+    if stage_committed {
+        record_setup(program_state);
+    }
+    if import_csv {
+        import_teams_action(program_state);
+    }
+}
+
+// draws the preview of the imported CSV file with the column mapping
+fn draw_team_import_window(
+    ui: &Ui,
+    new_screen_state: &mut NewScreenState,
+    data: &mut CompetitionData,
+    size: [f32; 2],
+) {
+    if new_screen_state.team_import.is_none() && new_screen_state.team_import_err_msg.is_none() {
+        return;
+    }
+
+    let mut opened = true;
+    let mut apply = false;
+    Window::new("Import teams")
+        .collapsible(false)
+        .opened(&mut opened)
+        .position(
+            [size[0] * (0.5 - 0.35), size[1] * (0.5 - 0.35)],
+            Condition::Appearing,
+        )
+        .size([size[0] * 0.7, size[1] * 0.7], Condition::Appearing)
+        .build(ui, || {
+            if let Some(msg) = new_screen_state.team_import_err_msg.as_ref() {
+                ui.text(msg);
+            }
+
+            let team_import = match new_screen_state.team_import.as_mut() {
+                Some(team_import) => team_import,
+                None => return,
+            };
+
+            // the column mapping, one combo per column of the CSV file
+            ui.text("Assign the columns of the file:");
+            team_import
+                .headers
+                .iter()
+                .zip(team_import.columns.iter_mut())
+                .enumerate()
+                .for_each(|(column_idx, (header, column))| {
+                    let item_width_token = ui.push_item_width(size[0] / 8.0);
+                    if let Some(_combo_token) = ui.begin_combo(
+                        format!("{header}##import_column_{column_idx}"),
+                        column.to_string(),
+                    ) {
+                        ImportColumn::all().iter().for_each(|option| {
+                            if Selectable::new(option.to_string())
+                                .selected(option == column)
+                                .build(ui)
+                            {
+                                *column = *option;
+                            }
+                        });
+                    }
+                    item_width_token.pop(ui);
+                });
+
+            ui.separator();
+
+            let rows = team_import.rows(data);
+            let count_ok = rows
+                .iter()
+                .filter(|row| row.status == ImportRowStatus::Ok)
+                .count();
+            ui.text(format!(
+                "{count_ok} of {} teams can be imported, the competition has {} places.",
+                rows.len(),
+                data.count_teams
+            ));
+
+            ChildWindow::new("##import_preview")
+                .size([0.0, ui.content_region_avail()[1] - 40.0])
+                .build(ui, || {
+                    if let Some(_table_token) = ui.begin_table_with_flags(
+                        "##import_preview_table",
+                        5,
+                        TableFlags::BORDERS | TableFlags::ROW_BG,
+                    ) {
+                        ["Group", "Team", "Region", "Players", "Status"]
+                            .iter()
+                            .for_each(|name| ui.table_setup_column(name));
+                        ui.table_headers_row();
+
+                        rows.iter().for_each(|row| {
+                            ui.table_next_row();
+                            ui.table_next_column();
+                            ui.text(match (row.group_name.as_ref(), row.group_idx) {
+                                (Some(group_name), _) => group_name.clone(),
+                                (None, Some(group_idx)) => format!("Group {}", group_idx + 1),
+                                (None, None) => String::from("-"),
+                            });
+                            ui.table_next_column();
+                            ui.text(&row.team.name);
+                            ui.table_next_column();
+                            ui.text(&row.team.region);
+                            ui.table_next_column();
+                            ui.text(
+                                row.team
+                                    .player_names
+                                    .iter()
+                                    .flatten()
+                                    .cloned()
+                                    .collect::<Vec<String>>()
+                                    .join(", "),
+                            );
+                            ui.table_next_column();
+                            // flag the rows which are not imported
+                            if row.status == ImportRowStatus::Ok {
+                                ui.text(row.status.to_string());
+                            } else {
+                                ui.text_colored([1.0, 0.3, 0.3, 1.0], row.status.to_string());
+                            }
+                        });
+                    }
+                });
+
+            if ui.button("Import") {
+                apply = true;
+            }
+            ui.same_line();
+            ui.text("This replaces all entered group and team names.");
         });
+
+    if apply {
+        if let Some(team_import) = new_screen_state.team_import.as_ref() {
+            team_import.apply(data);
+        }
+        opened = false;
+    }
+
+    if !opened {
+        new_screen_state.team_import = None;
+        new_screen_state.team_import_err_msg = None;
+    }
 }
 
 fn build_player_names(ui: &Ui, program_state: &mut ProgramState, menu_bar_height: f32) {
@@ -550,12 +759,14 @@ fn build_player_names(ui: &Ui, program_state: &mut ProgramState, menu_bar_height
                     assert!(check_valid_inputs(data, new_screen_state.stage).is_none());
                 }
 
+                record_setup(program_state);
                 program_state.switch_to_stage(ProgramStage::CurrentErgViewStage);
                 return;
             }
 
             ui.same_line();
             if ui.button("Skip") {
+                record_setup(program_state);
                 program_state.switch_to_stage(ProgramStage::CurrentErgViewStage);
                 return;
             }
@@ -629,6 +840,13 @@ fn build_player_names(ui: &Ui, program_state: &mut ProgramState, menu_bar_height
 
 // Helper
 
+// the setup has no journal events of its own, so each committed stage writes a snapshot to the journal
+fn record_setup(program_state: &mut ProgramState) {
+    if let Err(msg) = program_state.competition.record_journal_snapshot() {
+        eprintln!("[record_setup]: {msg}");
+    }
+}
+
 fn check_valid_inputs(data: &CompetitionData, stage: NewScreenStage) -> Option<String> {
     match stage {
         NewScreenStage::GeneralInfo => {
@@ -636,6 +854,8 @@ fn check_valid_inputs(data: &CompetitionData, stage: NewScreenStage) -> Option<S
                 Some("Enter a name for the competition!".to_string())
             } else if data.date_string == "" {
                 Some("Enter a date when the competition takes place!".to_string())
+            } else if data.start_time != "" && data.batch_start_time(0).is_none() {
+                Some("Enter the date as DD.MM.YYYY and the start time as HH:MM!".to_string())
             } else if data.place == "" {
                 Some("Enter a place where the competition takes place!".to_string())
             } else if data.executor == "" {
@@ -727,6 +947,8 @@ pub struct NewScreenState {
     pub go_back_popup: bool,
     pub restart_popup: bool,
     pub selected_team: Option<usize>,
+    pub team_import: Option<TeamImport>, // the CSV file to import, shown in the import window
+    pub team_import_err_msg: Option<String>,
 }
 
 impl NewScreenState {
@@ -738,6 +960,8 @@ impl NewScreenState {
             go_back_popup: false,
             restart_popup: false,
             selected_team: None,
+            team_import: None,
+            team_import_err_msg: None,
         }
     }
 
@@ -759,6 +983,8 @@ impl NewScreenState {
 
                 data.name = String::from("");
                 data.date_string = String::from("");
+                data.start_time = String::from("");
+                data.batch_duration_minutes = DEFAULT_BATCH_DURATION_MINUTES;
                 data.place = String::from("");
                 data.executor = String::from("");
                 data.organizer = String::from("");
@@ -0,0 +1,309 @@
+use std::{
+    io::{Cursor, Read},
+    sync::mpsc::{channel, Sender},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::data::{journal::LaneResult, CompetitionData, MatchResult};
+
+// The local REST API for club tools and scripts. All groups, teams, batches and lanes are addressed by
+// their index starting at 0, like in the competition file. The responses are JSON, errors have the form
+// {"error": "<message>"}.
+//
+// GET  /api/competition                 the metadata of the competition and the current batch of each group
+// GET  /api/teams                       the teams of each group with their players
+// GET  /api/schedule                    all matches including the breaks and the entered points
+// GET  /api/standings                   the current standings of each group
+// POST /api/groups/<group>/results      submits the results of the current batch of a group,
+//                                       body: {"batch": 0, "results": [{"lane": 0, "points": [7, 3]}]}
+//
+// Submitting results needs the PIN of the entry page in the header "X-ISRAT-PIN". The results are checked
+// like the results entered in the GUI and the batch has to be complete. They are refused while the group has
+// results entered in the GUI or on the tablets which are not submitted yet.
+
+const MAX_BODY_SIZE: u64 = 64 * 1024;
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(5); // the GUI handles the submission in its next frame
+
+// results submitted via the API, the GUI applies them and sends back the outcome
+pub struct ApiSubmission {
+    pub group_idx: usize,
+    pub batch: u32, // checked again by the GUI, the published data may be outdated
+    pub results: Vec<LaneResult>,
+    pub reply: Sender<Result<(), String>>,
+}
+
+#[derive(Deserialize)]
+struct ResultsBody {
+    batch: u32,
+    results: Vec<LaneResult>,
+}
+
+fn json_response(status: u16, value: Value) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(
+            Header::from_bytes(
+                &b"Content-Type"[..],
+                &b"application/json; charset=utf-8"[..],
+            )
+            .unwrap(),
+        )
+}
+
+pub fn error_response(status: u16, msg: &str) -> Response<Cursor<Vec<u8>>> {
+    json_response(status, json!({ "error": msg }))
+}
+
+fn get_competition_json(data: &CompetitionData) -> Value {
+    json!({
+        "name": data.name,
+        "date": data.date_string,
+        "place": data.place,
+        "executor": data.executor,
+        "organizer": data.organizer,
+        "referee": data.referee,
+        "competition_manager": data.competition_manager,
+        "clerk": data.clerk,
+        "additional_text": data.additional_text,
+        "group_names": data.group_names,
+        "teams_per_group": data.team_distribution[1],
+        "current_batch": data.current_batch,
+    })
+}
+
+fn get_teams_json(data: &CompetitionData) -> Value {
+    Value::Array(
+        data.teams
+            .as_ref()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(group_idx, teams)| {
+                json!({
+                    "group": group_idx,
+                    "teams": teams.iter().enumerate().map(|(team_idx, team)| json!({
+                        "team": team_idx,
+                        "name": team.name,
+                        "region": team.region,
+                        "players": team.player_names.iter().flatten().collect::<Vec<&String>>(),
+                    })).collect::<Vec<Value>>(),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn get_schedule_json(data: &CompetitionData) -> Value {
+    Value::Array(
+        data.matches
+            .iter()
+            .enumerate()
+            .flat_map(|(group_idx, matches)| {
+                matches.iter().map(move |_match| {
+                    let is_break = _match.result == MatchResult::Break;
+                    json!({
+                        "group": group_idx,
+                        "batch": _match.batch,
+                        "lane": if is_break { None } else { Some(_match.lane) },
+                        "team_a": _match.team_a,
+                        "team_b": if is_break { None } else { Some(_match.team_b) },
+                        "points": _match.points,
+                        "result": _match.result.to_string(),
+                    })
+                })
+            })
+            .collect(),
+    )
+}
+
+fn get_standings_json(data: &CompetitionData) -> Value {
+    Value::Array(
+        (0..data.teams.as_ref().unwrap().len())
+            .map(|group_idx| {
+                json!({
+                    "group": group_idx,
+                    "standings": data
+                        .calc_interim_result_for_group(group_idx)
+                        .iter()
+                        .enumerate()
+                        .map(|(place_idx, entry)| json!({
+                            "place": place_idx + 1,
+                            "team": entry.team_idx,
+                            "match_points": entry.match_points,
+                            "stock_points": entry.stock_points,
+                            "quotient": entry.quotient,
+                        }))
+                        .collect::<Vec<Value>>(),
+                })
+            })
+            .collect(),
+    )
+}
+
+// answers the read requests from the published copy of the competition data
+pub fn get_api_response(path: &str, data: Option<&CompetitionData>) -> Response<Cursor<Vec<u8>>> {
+    let data = match data {
+        Some(data) if data.teams.is_some() => data,
+        _ => return error_response(404, "No competition is loaded."),
+    };
+    match path {
+        "/api/competition" => json_response(200, get_competition_json(data)),
+        "/api/teams" => json_response(200, get_teams_json(data)),
+        "/api/schedule" => json_response(200, get_schedule_json(data)),
+        "/api/standings" => json_response(200, get_standings_json(data)),
+        _ => error_response(404, "Unknown API endpoint."),
+    }
+}
+
+// the group of a result submission, i.e. /api/groups/<group>/results
+pub fn parse_submit_path(path: &str) -> Option<usize> {
+    path.strip_prefix("/api/groups/")?
+        .strip_suffix("/results")?
+        .parse()
+        .ok()
+}
+
+pub fn has_valid_pin(request: &Request, pin: &str) -> bool {
+    request
+        .headers()
+        .iter()
+        .any(|header| header.field.equiv("X-ISRAT-PIN") && header.value.as_str() == pin)
+}
+
+// checks the method and the PIN of a result submission, the server counts the wrong PINs before the submission
+// waits for the GUI, so that parallel requests can not get around the lockout
+pub fn check_submit_request(request: &Request, pin: &str) -> Result<(), Response<Cursor<Vec<u8>>>> {
+    if *request.method() != Method::Post {
+        return Err(error_response(
+            405,
+            "Results have to be submitted with POST.",
+        ));
+    }
+    if !has_valid_pin(request, pin) {
+        return Err(error_response(
+            401,
+            "The header X-ISRAT-PIN is missing or wrong.",
+        ));
+    }
+    Ok(())
+}
+
+// checks a result submission against the published data and passes it to the GUI, which applies it,
+// the method and the PIN were checked with check_submit_request before
+pub fn submit_results(
+    request: &mut Request,
+    group_idx: usize,
+    data: Option<CompetitionData>,
+    submissions: &Sender<ApiSubmission>,
+) -> Response<Cursor<Vec<u8>>> {
+    let data = match data {
+        Some(data) if data.teams.is_some() => data,
+        _ => return error_response(404, "No competition is loaded."),
+    };
+
+    let mut body = String::new();
+    if request
+        .as_reader()
+        .take(MAX_BODY_SIZE)
+        .read_to_string(&mut body)
+        .is_err()
+    {
+        return error_response(400, "The request body could not be read.");
+    }
+    let body: ResultsBody = match serde_json::from_str(&body) {
+        Ok(body) => body,
+        Err(err) => return error_response(400, &format!("Invalid request body: {err}")),
+    };
+
+    // the batch is part of the request, so that a repeated request can not overwrite the next batch,
+    // this check against the published data only answers early, the GUI checks the batch again
+    match data.current_batch.get(group_idx) {
+        Some(current_batch) if *current_batch == body.batch => (),
+        Some(_) => return error_response(409, "The batch is not the current batch of the group."),
+        None => return error_response(404, "Unknown group."),
+    }
+    if let Err(msg) = data.check_batch_results(group_idx, &body.results) {
+        return error_response(422, &msg);
+    }
+
+    let (reply, reply_receiver) = channel();
+    let submission = ApiSubmission {
+        group_idx,
+        batch: body.batch,
+        results: body.results,
+        reply,
+    };
+    if submissions.send(submission).is_err() {
+        return error_response(503, "The server is shutting down.");
+    }
+    match reply_receiver.recv_timeout(SUBMIT_TIMEOUT) {
+        Ok(Ok(_)) => json_response(200, json!({ "submitted": true })),
+        // the results were checked above, so the GUI rejects them because of its state, e.g. unsubmitted results
+        Ok(Err(msg)) => error_response(409, &msg),
+        Err(_) => error_response(503, "ISRAT did not respond in time."),
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_api() {
+    use crate::data::Match;
+
+    assert_eq!(parse_submit_path("/api/groups/1/results"), Some(1));
+    assert_eq!(parse_submit_path("/api/groups/x/results"), None);
+
+    let mut data = crate::data::two_team_competition();
+    data.matches[0].push(Match {
+        team_a: 1,
+        team_b: 1,
+        points: None,
+        result: MatchResult::Break,
+        batch: 0,
+        lane: u32::MAX,
+    });
+
+    let schedule = get_schedule_json(&data);
+    assert_eq!(schedule[1]["lane"], Value::Null);
+    assert_eq!(get_teams_json(&data)[0]["teams"][1]["name"], "TSV Beispiel");
+
+    // the breaks need no result, but every match does
+    assert!(data.check_batch_results(0, &[]).is_err());
+    assert!(data
+        .check_batch_results(
+            0,
+            &[LaneResult {
+                lane: 1,
+                points: [7, 3]
+            }]
+        )
+        .is_err());
+    let results = vec![LaneResult {
+        lane: 0,
+        points: [7, 3],
+    }];
+    let event = data.submit_batch_results(0, 0, results.clone()).unwrap();
+    assert!(matches!(
+        event,
+        crate::data::journal::JournalEvent::BatchSubmitted { batch: 0, .. }
+    ));
+    assert_eq!(data.current_batch, vec![1]);
+
+    // a repeated submission of the same batch, e.g. a retry after a timeout, is not applied to the next batch
+    data.matches[0].push(Match {
+        team_a: 1,
+        team_b: 0,
+        points: None,
+        result: MatchResult::NotPlayed,
+        batch: 1,
+        lane: 0,
+    });
+    data.current_batch = vec![1];
+    assert!(data.submit_batch_results(0, 0, results).is_err());
+    assert_eq!(data.current_batch, vec![1]);
+    assert_eq!(data.matches[0][2].points, None);
+    assert_eq!(get_standings_json(&data)[0]["standings"][0]["team"], 0);
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::Read,
+    sync::mpsc::Sender,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use tiny_http::Request;
+
+use crate::data::{
+    html_export::{escape_html, get_live_page},
+    match_id, parse_match_id, CompetitionData, MatchResult,
+};
+
+// The result entry form for the lane referees. The entered results are sent to the GUI as pending results,
+// the clerk has to confirm them before the batch can be submitted.
+
+const MAX_FORM_SIZE: u64 = 4096;
+
+// a result entered on a tablet, which is not yet confirmed by the clerk
+pub struct PendingResult {
+    pub group_idx: usize,
+    pub batch: u32,
+    pub lane: u32,
+    pub points: [i32; 2],
+}
+
+// the PIN the referees need to enter results, a new one is generated for every server start
+pub fn generate_pin() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos()),
+    );
+    format!("{:06}", hasher.finish() % 1_000_000)
+}
+
+fn decode_form_value(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let hex_byte = bytes
+            .get(idx + 1..idx + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[idx], hex_byte) {
+            (b'+', _) => decoded.push(b' '),
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                idx += 2;
+            }
+            (byte, _) => decoded.push(byte),
+        }
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// decodes an application/x-www-form-urlencoded body into its key value pairs
+pub fn parse_form(body: &str) -> Vec<(String, String)> {
+    body.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => (decode_form_value(key), decode_form_value(value)),
+            None => (decode_form_value(pair), String::new()),
+        })
+        .collect()
+}
+
+pub fn read_form(request: &mut Request) -> Vec<(String, String)> {
+    let mut body = String::new();
+    match request
+        .as_reader()
+        .take(MAX_FORM_SIZE)
+        .read_to_string(&mut body)
+    {
+        Ok(_) => parse_form(&body),
+        Err(_) => vec![],
+    }
+}
+
+fn form_value<'a>(form: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    form.iter()
+        .find(|(form_key, _)| form_key == key)
+        .map(|(_, value)| value.as_str())
+}
+
+// checks the entered result against the current batch of the competition
+fn validate_entry(
+    data: &CompetitionData,
+    form: &[(String, String)],
+) -> Result<PendingResult, String> {
+    // the match id contains the batch, so that a form of a finished batch is rejected
+    let (group_idx, batch, lane) = match parse_match_id(form_value(form, "match").unwrap_or("")) {
+        Some(ids) => ids,
+        None => return Err(String::from("Bitte eine Begegnung auswählen.")),
+    };
+
+    if data.current_batch.get(group_idx) != Some(&batch) {
+        return Err(String::from(
+            "Der Durchgang ist bereits abgeschlossen, bitte die Seite neu laden.",
+        ));
+    }
+    let is_match = data.matches[group_idx].iter().any(|_match| {
+        _match.batch == batch && _match.lane == lane && _match.result != MatchResult::Break
+    });
+    if !is_match {
+        return Err(String::from("Die Begegnung existiert nicht."));
+    }
+
+    let points = ["points_a", "points_b"].map(|key| {
+        form_value(form, key)
+            .and_then(|points| points.trim().parse::<i32>().ok())
+            .filter(|points| *points >= 0)
+    });
+    match points {
+        [Some(points_a), Some(points_b)] => Ok(PendingResult {
+            group_idx,
+            batch,
+            lane,
+            points: [points_a, points_b],
+        }),
+        _ => Err(String::from(
+            "Bitte für beide Mannschaften gültige Punkte eingeben.",
+        )),
+    }
+}
+
+// handles a request of the entry form, returns the page and whether a wrong PIN was entered
+// the query of the url may preselect a match, e.g. from the QR code of a lane card
+pub fn get_entry_page(
+    data: Option<&CompetitionData>,
+    form: &[(String, String)],
+    query: &[(String, String)],
+    pin: &str,
+    submissions: &Sender<PendingResult>,
+) -> (String, bool) {
+    let data = match data {
+        Some(data) if data.teams.is_some() => data,
+        _ => return (CompetitionData::get_no_competition_html(), false),
+    };
+
+    let entered_pin = form_value(form, "pin").unwrap_or("");
+    let mut wrong_pin = false;
+    let message = if form.is_empty() {
+        String::new()
+    } else if entered_pin != pin {
+        wrong_pin = true;
+        String::from(r#"<p class="message error">Die PIN ist falsch.</p>"#)
+    } else {
+        match validate_entry(data, form) {
+            Ok(result) => {
+                let lane = result.lane;
+                match submissions.send(result) {
+                    Ok(_) => format!(
+                        r#"<p class="message">Das Ergebnis von Bahn {} wurde übermittelt und wartet auf die Bestätigung der Schriftführung.</p>"#,
+                        lane + 1
+                    ),
+                    Err(_) => String::from(
+                        r#"<p class="message error">Die Übermittlung ist fehlgeschlagen.</p>"#,
+                    ),
+                }
+            }
+            Err(msg) => format!(r#"<p class="message error">{}</p>"#, escape_html(&msg)),
+        }
+    };
+
+    let selected_match = form_value(form, "match").or_else(|| form_value(query, "match"));
+    let options = data
+        .matches
+        .iter()
+        .enumerate()
+        .flat_map(|(group_idx, matches)| {
+            let batch = data.current_batch[group_idx];
+            let mut matches: Vec<_> = matches
+                .iter()
+                .filter(|_match| _match.batch == batch && _match.result != MatchResult::Break)
+                .collect();
+            matches.sort_by_key(|_match| _match.lane);
+            matches.into_iter().map(move |_match| {
+                let teams = &data.teams.as_ref().unwrap()[group_idx];
+                let id = match_id(group_idx, batch, _match.lane);
+                format!(
+                    r#"<option value="{id}"{}>{} – Bahn {}: {} – {}</option>"#,
+                    if selected_match == Some(id.as_str()) {
+                        " selected"
+                    } else {
+                        ""
+                    },
+                    escape_html(&data.group_names.as_ref().unwrap()[group_idx]),
+                    _match.lane + 1,
+                    escape_html(&teams[_match.team_a].name),
+                    escape_html(&teams[_match.team_b].name),
+                )
+            })
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let body = format!(
+        r#"<section>
+{message}
+<form method="post" action="entry">
+  <label for="match">Begegnung</label>
+  <select id="match" name="match">{options}</select>
+  <label for="points_a">Punkte Mannschaft A</label>
+  <input id="points_a" name="points_a" type="number" min="0" inputmode="numeric" required>
+  <label for="points_b">Punkte Mannschaft B</label>
+  <input id="points_b" name="points_b" type="number" min="0" inputmode="numeric" required>
+  <label for="pin">PIN</label>
+  <input id="pin" name="pin" type="password" inputmode="numeric" value="{pin_value}" required>
+  <button type="submit">Ergebnis übermitteln</button>
+</form>
+</section>
+"#,
+        // the PIN is kept for the next entry after it was entered correctly once
+        pin_value = if wrong_pin {
+            String::new()
+        } else {
+            escape_html(entered_pin)
+        },
+    );
+
+    (
+        get_live_page(
+            &format!("Ergebniseingabe – {}", data.name),
+            "",
+            &body,
+            false,
+        ),
+        wrong_pin,
+    )
+}
+
+// shown instead of the entry page after too many wrong PINs from the same device
+pub fn get_locked_out_page() -> String {
+    get_live_page(
+        "Ergebniseingabe",
+        "",
+        r#"<section>
+<p class="message error">Zu viele falsche PINs. Bitte in einer Minute erneut versuchen.</p>
+</section>
+"#,
+        false,
+    )
+}
+
+#[cfg(test)]
+#[test]
+fn test_entry_form() {
+    use std::sync::mpsc::channel;
+
+    assert_eq!(
+        parse_form("match=0-1-2&pin=12%2034&name=a+b%C3%BC%"),
+        vec![
+            (String::from("match"), String::from("0-1-2")),
+            (String::from("pin"), String::from("12 34")),
+            (String::from("name"), String::from("a bü%")),
+        ]
+    );
+
+    let data = crate::data::two_team_competition();
+
+    let (sender, receiver) = channel();
+    let (_, wrong_pin) = get_entry_page(
+        Some(&data),
+        &parse_form("match=0-0-0&points_a=7&points_b=3&pin=000000"),
+        &[],
+        "123456",
+        &sender,
+    );
+    assert!(wrong_pin);
+    assert!(receiver.try_recv().is_err());
+
+    // a result of a finished batch is rejected
+    get_entry_page(
+        Some(&data),
+        &parse_form("match=0-1-0&points_a=7&points_b=3&pin=123456"),
+        &[],
+        "123456",
+        &sender,
+    );
+    assert!(receiver.try_recv().is_err());
+
+    get_entry_page(
+        Some(&data),
+        &parse_form("match=0-0-0&points_a=7&points_b=3&pin=123456"),
+        &[],
+        "123456",
+        &sender,
+    );
+    let result = receiver.try_recv().unwrap();
+    assert_eq!((result.group_idx, result.lane), (0, 0));
+    assert_eq!(result.points, [7, 3]);
+
+    // the QR code of a lane card preselects its match
+    let (html, _) = get_entry_page(
+        Some(&data),
+        &[],
+        &parse_form("match=0-0-0"),
+        "123456",
+        &sender,
+    );
+    assert!(html.contains(r#"<option value="0-0-0" selected>"#));
+}
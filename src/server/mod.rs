@@ -0,0 +1,479 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    net::{IpAddr, SocketAddr, UdpSocket},
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use self::{
+    api::{
+        check_submit_request, error_response, get_api_response, parse_submit_path, submit_results,
+        ApiSubmission,
+    },
+    entry::{
+        generate_pin, get_entry_page, get_locked_out_page, parse_form, read_form, PendingResult,
+    },
+};
+
+use crate::{
+    data::{html_export::STYLESHEET, CompetitionData},
+    screens::erg_screen::submit_external_results,
+    ProgramState,
+};
+
+pub mod api;
+pub mod entry;
+
+// The embedded web server serves the live results to the spectators in the hall network.
+// The GUI publishes a copy of the competition data whenever it changes, the open pages are
+// notified via server-sent events and reload themselves. The lane referees enter their results
+// on the entry page, which are sent back to the GUI for the confirmation by the clerk. Other tools
+// use the REST API described in api.rs.
+
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(500); // how often the GUI checks for changes
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15); // keeps idle event streams open behind proxies
+const MAX_WRONG_PINS: u32 = 5; // the wrong PINs a client may enter before it is locked out
+const WRONG_PIN_LOCKOUT: Duration = Duration::from_secs(60); // stops guessing the PIN without slowing down the other clients
+
+// the state shared between the GUI and the server threads
+struct SharedState {
+    data: Option<CompetitionData>,
+    subscribers: Vec<Sender<()>>, // one sender for each open event stream
+    pin: String,
+    submissions: Sender<PendingResult>, // the results entered on the entry page
+    api_submissions: Sender<ApiSubmission>, // the results submitted via the REST API
+    pin_guard: PinGuard,
+}
+
+// counts the wrong PINs of each client address, a client is locked out after too many of them
+struct PinGuard {
+    failures: HashMap<Option<IpAddr>, PinFailures>,
+}
+
+#[derive(Default)]
+struct PinFailures {
+    count: u32,
+    locked_until: Option<Instant>,
+}
+
+impl PinGuard {
+    fn new() -> Self {
+        PinGuard {
+            failures: HashMap::new(),
+        }
+    }
+
+    // whether the client has to wait until it may enter a PIN again
+    fn is_locked_out(&mut self, client: Option<IpAddr>, now: Instant) -> bool {
+        match self
+            .failures
+            .get(&client)
+            .and_then(|failures| failures.locked_until)
+        {
+            Some(locked_until) if now < locked_until => true,
+            Some(_) => {
+                // the lockout is over, the client gets all attempts again
+                self.failures.remove(&client);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn record_attempt(&mut self, client: Option<IpAddr>, correct_pin: bool, now: Instant) {
+        if correct_pin {
+            self.failures.remove(&client);
+            return;
+        }
+        let failures = self.failures.entry(client).or_default();
+        failures.count += 1;
+        if failures.count >= MAX_WRONG_PINS {
+            failures.locked_until = Some(now + WRONG_PIN_LOCKOUT);
+        }
+    }
+}
+
+pub struct LiveServer {
+    http: Arc<Server>,
+    shared: Arc<Mutex<SharedState>>,
+    handle: Option<JoinHandle<()>>,
+    submissions: Receiver<PendingResult>,
+    api_submissions: Receiver<ApiSubmission>,
+    pub port: u16,
+    pub pin: String, // the PIN needed to enter results
+    pub url: String, // the address shown to the user, the spectators have to use the address of this computer in the hall network
+}
+
+impl LiveServer {
+    pub fn start(port: u16) -> Result<Self, String> {
+        let http = match Server::http(SocketAddr::from(([0, 0, 0, 0], port))) {
+            Ok(http) => Arc::new(http),
+            Err(err) => return Err(format!("Starting the server on port {port} failed: {err}")),
+        };
+        let port = http.server_addr().to_ip().map_or(port, |addr| addr.port());
+
+        let pin = generate_pin();
+        let (submissions_sender, submissions) = channel();
+        let (api_submissions_sender, api_submissions) = channel();
+        let shared = Arc::new(Mutex::new(SharedState {
+            data: None,
+            subscribers: vec![],
+            pin: pin.clone(),
+            submissions: submissions_sender,
+            api_submissions: api_submissions_sender,
+            pin_guard: PinGuard::new(),
+        }));
+        let handle = {
+            let http = http.clone();
+            let shared = shared.clone();
+            thread::spawn(move || {
+                for request in http.incoming_requests() {
+                    handle_request(request, &shared);
+                }
+            })
+        };
+
+        Ok(LiveServer {
+            http,
+            shared,
+            handle: Some(handle),
+            submissions,
+            api_submissions,
+            port,
+            pin,
+            url: format!("http://{}:{port}/", local_ip()),
+        })
+    }
+
+    // replaces the served competition data and notifies all open pages
+    pub fn publish(&self, data: Option<CompetitionData>) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.data = data;
+        shared
+            .subscribers
+            .retain(|subscriber| subscriber.send(()).is_ok());
+    }
+
+    pub fn stop(mut self) {
+        self.http.unblock();
+        // dropping the senders closes the open event streams
+        self.shared.lock().unwrap().subscribers.clear();
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                eprintln!("[LiveServer]: The server thread panicked!");
+            }
+        }
+    }
+}
+
+// determines the address of the network interface used for outgoing traffic, no packet is sent for this
+fn local_ip() -> String {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect("192.168.0.1:80").map(|_| socket))
+        .and_then(|socket| socket.local_addr())
+        .map_or(String::from("127.0.0.1"), |addr| addr.ip().to_string())
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], value.as_bytes()).unwrap()
+}
+
+fn handle_request(mut request: Request, shared: &Arc<Mutex<SharedState>>) {
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+    let client = request.remote_addr().map(|addr| addr.ip());
+    let response = match path.as_str() {
+        "/" | "/index.html" => {
+            let html = match shared.lock().unwrap().data.as_ref() {
+                Some(data) if data.teams.is_some() => data.get_live_html(),
+                _ => CompetitionData::get_no_competition_html(),
+            };
+            Response::from_string(html).with_header(content_type("text/html; charset=utf-8"))
+        }
+        "/entry" => {
+            let form = if *request.method() == Method::Post {
+                read_form(&mut request)
+            } else {
+                vec![]
+            };
+            let query = parse_form(request.url().split_once('?').map_or("", |(_, query)| query));
+            let mut shared = shared.lock().unwrap();
+            let now = Instant::now();
+            if !form.is_empty() && shared.pin_guard.is_locked_out(client, now) {
+                Response::from_string(get_locked_out_page())
+                    .with_status_code(429)
+                    .with_header(content_type("text/html; charset=utf-8"))
+            } else {
+                let (html, wrong_pin) = get_entry_page(
+                    shared.data.as_ref(),
+                    &form,
+                    &query,
+                    &shared.pin,
+                    &shared.submissions,
+                );
+                if !form.is_empty() {
+                    shared.pin_guard.record_attempt(client, !wrong_pin, now);
+                }
+                Response::from_string(html).with_header(content_type("text/html; charset=utf-8"))
+            }
+        }
+        "/style.css" => {
+            Response::from_string(STYLESHEET).with_header(content_type("text/css; charset=utf-8"))
+        }
+        "/events" => {
+            let (sender, receiver) = channel();
+            shared.lock().unwrap().subscribers.push(sender);
+            // the stream is kept open until the page is closed, so it gets its own thread
+            thread::spawn(move || {
+                let mut writer = request.into_writer();
+                let mut write_res = writer
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n",
+                    )
+                    .and_then(|_| writer.flush());
+                while write_res.is_ok() {
+                    let message: &[u8] = match receiver.recv_timeout(KEEP_ALIVE_INTERVAL) {
+                        Ok(_) => b"event: update\ndata: \n\n",
+                        Err(RecvTimeoutError::Timeout) => b": keep-alive\n\n",
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    };
+                    write_res = writer.write_all(message).and_then(|_| writer.flush());
+                }
+            });
+            return;
+        }
+        api_path if api_path.starts_with("/api/") => match parse_submit_path(api_path) {
+            Some(group_idx) => {
+                // the PIN is checked and counted under the lock, so that parallel requests can not get around the
+                // lockout, the submission waits for the GUI, so it gets its own thread and the shared state is unlocked
+                let checked_submission = {
+                    let mut shared = shared.lock().unwrap();
+                    let now = Instant::now();
+                    if shared.pin_guard.is_locked_out(client, now) {
+                        Err(error_response(
+                            429,
+                            "Too many wrong PINs, please try again later.",
+                        ))
+                    } else {
+                        let check_res = check_submit_request(&request, &shared.pin);
+                        // a wrong method is no attempt to guess the PIN
+                        if *request.method() == Method::Post {
+                            shared
+                                .pin_guard
+                                .record_attempt(client, check_res.is_ok(), now);
+                        }
+                        check_res.map(|_| (shared.data.clone(), shared.api_submissions.clone()))
+                    }
+                };
+                let (data, api_submissions) = match checked_submission {
+                    Ok(submission) => submission,
+                    Err(response) => {
+                        if request.respond(response).is_err() {
+                            eprintln!("[LiveServer]: Sending the response to {path} failed!");
+                        }
+                        return;
+                    }
+                };
+                let path = path.clone();
+                thread::spawn(move || {
+                    let response = submit_results(&mut request, group_idx, data, &api_submissions);
+                    if request.respond(response).is_err() {
+                        eprintln!("[LiveServer]: Sending the response to {path} failed!");
+                    }
+                });
+                return;
+            }
+            None => get_api_response(api_path, shared.lock().unwrap().data.as_ref()),
+        },
+        _ => Response::from_string("Not found").with_status_code(404),
+    };
+
+    if request.respond(response).is_err() {
+        eprintln!("[LiveServer]: Sending the response to {path} failed!");
+    }
+}
+
+pub struct ServerState {
+    pub server: Option<LiveServer>,
+    pub err_msg: Option<String>,
+    last_check: Instant,
+    published_hash: Option<u64>, // the hash of the competition data the server knows
+}
+
+impl ServerState {
+    pub fn new() -> Self {
+        ServerState {
+            server: None,
+            err_msg: None,
+            last_check: Instant::now(),
+            published_hash: None,
+        }
+    }
+}
+
+pub fn start_server_action(program_state: &mut ProgramState) {
+    let server_state = &mut program_state.server_state;
+    if server_state.server.is_some() {
+        return;
+    }
+
+    match LiveServer::start(program_state.settings.server_port) {
+        Ok(server) => {
+            server.publish(program_state.competition.data.clone());
+            server_state.published_hash = program_state.competition.data_hash();
+            server_state.server = Some(server);
+            server_state.err_msg = None;
+        }
+        Err(msg) => server_state.err_msg = Some(msg),
+    }
+}
+
+pub fn stop_server_action(program_state: &mut ProgramState) {
+    if let Some(server) = program_state.server_state.server.take() {
+        server.stop();
+    }
+}
+
+// publishes the competition data to the server, if it has changed since the last check,
+// and passes the results entered on the entry page to the result screen
+pub fn check_server_messages(program_state: &mut ProgramState) {
+    let server_state = &mut program_state.server_state;
+    let mut publish_due = server_state.last_check.elapsed() >= PUBLISH_INTERVAL;
+    let server = match server_state.server.as_ref() {
+        Some(server) => server,
+        None => return,
+    };
+
+    while let Ok(result) = server.submissions.try_recv() {
+        let is_current_batch = program_state
+            .competition
+            .data
+            .as_ref()
+            .map_or(false, |data| {
+                data.current_batch.get(result.group_idx) == Some(&result.batch)
+            });
+        match program_state.erg_screen_state.as_mut() {
+            Some(erg_screen_state) if is_current_batch => {
+                erg_screen_state.add_pending_result(result.group_idx, result.lane, result.points)
+            }
+            _ => eprintln!(
+                "[check_server_messages]: Ignoring entered result of a finished batch (group {}, batch {}, lane {})",
+                result.group_idx + 1,
+                result.batch + 1,
+                result.lane + 1
+            ),
+        }
+    }
+
+    // the submissions of the REST API are applied directly and published at once
+    let api_submissions: Vec<ApiSubmission> = server.api_submissions.try_iter().collect();
+    for submission in api_submissions {
+        let submit_res = submit_external_results(
+            program_state,
+            submission.group_idx,
+            submission.batch,
+            submission.results,
+        );
+        publish_due |= submit_res.is_ok();
+        if submission.reply.send(submit_res).is_err() {
+            eprintln!("[check_server_messages]: The API request was closed before the reply");
+        }
+    }
+
+    if !publish_due {
+        return;
+    }
+    let server_state = &mut program_state.server_state;
+    server_state.last_check = Instant::now();
+
+    let data_hash = program_state.competition.data_hash();
+    if data_hash != server_state.published_hash {
+        server_state
+            .server
+            .as_ref()
+            .unwrap()
+            .publish(program_state.competition.data.clone());
+        server_state.published_hash = data_hash;
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_live_server() {
+    use std::{io::Read, net::TcpStream};
+
+    let server = LiveServer::start(0).unwrap();
+    let mut data = crate::data::two_team_competition();
+    data.name = String::from("Livemeisterschaft");
+    server.publish(Some(data));
+
+    // negative points are rejected like on the entry page
+    let body = r#"{"batch": 0, "results": [{"lane": 0, "points": [-5, 3]}]}"#;
+    let mut api_stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+    api_stream
+        .write_all(
+            format!(
+                "POST /api/groups/0/results HTTP/1.1\r\nHost: localhost\r\nX-ISRAT-PIN: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                server.pin,
+                body.len()
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let mut api_response = String::new();
+    api_stream.read_to_string(&mut api_response).unwrap();
+    assert!(api_response.starts_with("HTTP/1.1 422"));
+    assert!(api_response.contains("must not be negative"));
+
+    // a client guessing the PIN is locked out, also with parallel requests, the other requests are answered anyway
+    let mut api_streams: Vec<TcpStream> = (0..2 * MAX_WRONG_PINS)
+        .map(|_| {
+            let mut api_stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+            api_stream
+                .write_all(b"POST /api/groups/0/results HTTP/1.1\r\nHost: localhost\r\nX-ISRAT-PIN: x\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            api_stream
+        })
+        .collect();
+    let status_codes: Vec<String> = api_streams
+        .iter_mut()
+        .map(|api_stream| {
+            let mut api_response = String::new();
+            api_stream.read_to_string(&mut api_response).unwrap();
+            api_response[9..12].to_string()
+        })
+        .collect();
+    let count_status = |status: &str| status_codes.iter().filter(|code| *code == status).count();
+    assert_eq!(count_status("401"), MAX_WRONG_PINS as usize);
+    assert_eq!(count_status("429"), MAX_WRONG_PINS as usize);
+
+    let mut stream = TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("Livemeisterschaft"));
+
+    // the lockout ends after a while and a correct PIN resets the count
+    let mut pin_guard = PinGuard::new();
+    let client = Some(IpAddr::from([192, 168, 0, 7]));
+    let now = Instant::now();
+    (0..MAX_WRONG_PINS - 1).for_each(|_| pin_guard.record_attempt(client, false, now));
+    pin_guard.record_attempt(client, true, now);
+    pin_guard.record_attempt(client, false, now);
+    assert!(!pin_guard.is_locked_out(client, now));
+    (0..MAX_WRONG_PINS).for_each(|_| pin_guard.record_attempt(client, false, now));
+    assert!(pin_guard.is_locked_out(client, now));
+    assert!(!pin_guard.is_locked_out(Some(IpAddr::from([192, 168, 0, 8])), now));
+    assert!(!pin_guard.is_locked_out(client, now + WRONG_PIN_LOCKOUT));
+
+    server.stop();
+}
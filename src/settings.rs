@@ -0,0 +1,107 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::data::export_jobs::TexBundle;
+
+// user settings, persisted as JSON in the config directory of ISRAT
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub backup_count: u32, // the number of backups kept next to the competition file, 0 disables the backups
+    pub autosave_interval_minutes: u32, // the interval between two autosaves, 0 disables the autosave
+    pub autosave_retention_days: u32, // autosaves older than this are removed at startup, 0 keeps them forever
+    pub server_port: u16,             // the port of the live results server
+    pub beamer_interval_seconds: u32, // the time each view of the public display is shown
+    pub tex_bundle_path: String, // a local TeX bundle (zip file or directory) for the PDF exports, empty for the online bundle
+    pub tex_offline: bool, // the online bundle is only read from the cache filled by preparing the offline exports
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            backup_count: 5,
+            autosave_interval_minutes: 1,
+            autosave_retention_days: 7,
+            server_port: 8080,
+            beamer_interval_seconds: 15,
+            tex_bundle_path: String::new(),
+            tex_offline: false,
+        }
+    }
+}
+
+impl Settings {
+    // loads the settings from the config directory, falls back to the defaults if no valid settings file exists
+    pub fn load() -> Self {
+        let path = match settings_file_path() {
+            Some(path) => path,
+            None => return Settings::default(),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(json_string) => match serde_json::from_str(&json_string) {
+                Ok(settings) => settings,
+                Err(_) => {
+                    eprintln!(
+                        "Settings file {} is not well-formatted, using defaults!",
+                        path.display()
+                    );
+                    Settings::default()
+                }
+            },
+            Err(_) => Settings::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = match settings_file_path() {
+            Some(path) => path,
+            None => return Err(String::from("Could not determine the config directory!")),
+        };
+
+        if let Some(parent) = path.parent() {
+            if fs::create_dir_all(parent).is_err() {
+                return Err(String::from("Creation of config dir failed!"));
+            }
+        }
+
+        let json_string = match serde_json::to_string_pretty(self) {
+            Ok(json_string) => json_string,
+            Err(_) => return Err(String::from("Serialization of the settings failed!")),
+        };
+
+        match fs::write(path, json_string) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(String::from("Write to settings file failed!")),
+        }
+    }
+
+    // the source of the TeX resources used by the PDF exports
+    pub fn tex_bundle(&self) -> TexBundle {
+        if !self.tex_bundle_path.trim().is_empty() {
+            TexBundle::Local(PathBuf::from(self.tex_bundle_path.trim()))
+        } else if self.tex_offline {
+            TexBundle::Cached
+        } else {
+            TexBundle::Online
+        }
+    }
+}
+
+// the directory used to store the settings and other user configuration
+pub fn config_dir() -> Option<PathBuf> {
+    let base_dir = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(config_home) => Some(PathBuf::from(config_home)),
+            None => std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")),
+        }
+    };
+    base_dir.map(|dir| dir.join("israt"))
+}
+
+fn settings_file_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("settings.json"))
+}
@@ -0,0 +1,135 @@
+use glium::glutin;
+use glium::glutin::event_loop::EventLoopWindowTarget;
+use glium::glutin::window::{Fullscreen, WindowBuilder, WindowId};
+use glium::{Display, Surface};
+use imgui::{Context, FontConfig, FontGlyphRanges, FontSource, SuspendedContext};
+use imgui_glium_renderer::Renderer;
+use std::time::Instant;
+
+use crate::beamer::{draw_beamer, set_beamer_style};
+use crate::ProgramState;
+
+// the second window for the public display, it has its own imgui context with large fonts
+pub struct BeamerWindow {
+    display: Display,
+    imgui: Option<SuspendedContext>, // only active while the public display is drawn
+    renderer: Renderer,
+    last_frame: Instant,
+}
+
+impl BeamerWindow {
+    // opens the window borderless full screen on the first monitor which is not the primary one
+    pub fn open(
+        window_target: &EventLoopWindowTarget<()>,
+        main_imgui: &mut Option<Context>,
+    ) -> Result<Self, String> {
+        let primary_monitor = window_target.primary_monitor();
+        let second_monitor = window_target
+            .available_monitors()
+            .find(|monitor| Some(monitor) != primary_monitor.as_ref());
+
+        let mut builder = WindowBuilder::new()
+            .with_title("ISRAT - Public display")
+            .with_inner_size(glutin::dpi::LogicalSize::new(1280f64, 720f64));
+        if second_monitor.is_some() {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(second_monitor)));
+        }
+
+        let gl_window = glutin::ContextBuilder::new()
+            .with_vsync(true)
+            .build_windowed(builder, window_target)
+            .map_err(|err| format!("Creating the public display failed: {err}"))?;
+        let display = Display::from_gl_window(gl_window)
+            .map_err(|err| format!("Creating the public display failed: {err}"))?;
+
+        let mut imgui = Some(SuspendedContext::create());
+        let renderer = with_context(main_imgui, &mut imgui, |context| {
+            context.set_ini_filename(None);
+            context.fonts().add_font(&[FontSource::TtfData {
+                data: include_bytes!("../../resources/mplus-1p-regular.ttf"),
+                size_pixels: 64.0,
+                config: Some(FontConfig {
+                    rasterizer_multiply: 1.75,
+                    glyph_ranges: FontGlyphRanges::japanese(),
+                    ..FontConfig::default()
+                }),
+            }]);
+            set_beamer_style(context.style_mut());
+            Renderer::init(context, &display)
+        })
+        .map_err(|err| format!("Creating the public display failed: {err}"))?;
+
+        Ok(BeamerWindow {
+            display,
+            imgui,
+            renderer,
+            last_frame: Instant::now(),
+        })
+    }
+
+    pub fn id(&self) -> WindowId {
+        self.display.gl_window().window().id()
+    }
+
+    pub fn request_redraw(&self) {
+        self.display.gl_window().window().request_redraw();
+    }
+
+    // draws one frame, the main imgui context is suspended meanwhile
+    pub fn render(&mut self, main_imgui: &mut Option<Context>, program_state: &mut ProgramState) {
+        let BeamerWindow {
+            display,
+            imgui,
+            renderer,
+            last_frame,
+        } = self;
+
+        with_context(main_imgui, imgui, |context| {
+            let now = Instant::now();
+            context.io_mut().update_delta_time(now - *last_frame);
+            *last_frame = now;
+
+            // the window is read-only, therefore only its size is passed to imgui instead of all events
+            let size = display.gl_window().window().inner_size();
+            let size = [size.width as f32, size.height as f32];
+            context.io_mut().display_size = size;
+            // the fonts grow with the window, they are rasterized for a 1080p projector
+            context.io_mut().font_global_scale = (size[1] / 1080.0).clamp(0.4, 2.0);
+
+            let ui = context.frame();
+            draw_beamer(&ui, program_state, size);
+
+            let mut target = display.draw();
+            target.clear_color_srgb(0.0, 0.0, 0.0, 1.0);
+            let draw_data = ui.render();
+            if let Err(err) = renderer.render(&mut target, draw_data) {
+                eprintln!("[BeamerWindow::render]: {err}");
+            }
+            if let Err(err) = target.finish() {
+                eprintln!("[BeamerWindow::render]: {err}");
+            }
+        });
+    }
+}
+
+// runs f with the context of the public display, imgui allows only one active context at a time
+fn with_context<R>(
+    main_imgui: &mut Option<Context>,
+    beamer_imgui: &mut Option<SuspendedContext>,
+    f: impl FnOnce(&mut Context) -> R,
+) -> R {
+    let suspended_main = main_imgui.take().unwrap().suspend();
+    let mut context = match beamer_imgui.take().unwrap().activate() {
+        Ok(context) => context,
+        Err(_) => unreachable!("the main context is suspended"),
+    };
+
+    let res = f(&mut context);
+
+    *beamer_imgui = Some(context.suspend());
+    *main_imgui = match suspended_main.activate() {
+        Ok(context) => Some(context),
+        Err(_) => unreachable!("the context of the public display is suspended"),
+    };
+    res
+}
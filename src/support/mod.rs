@@ -15,6 +15,9 @@ use std::time::Instant;
 
 use crate::ProgramState;
 
+use self::beamer_window::BeamerWindow;
+
+mod beamer_window;
 mod clipboard;
 
 pub struct System {
@@ -102,28 +105,65 @@ impl System {
         let System {
             event_loop,
             display,
-            mut imgui,
+            imgui,
             mut platform,
             mut renderer,
             ..
         } = self;
         let mut last_frame = Instant::now();
+        // the context is only taken while the public display is drawn, which has its own context
+        let mut imgui = Some(imgui);
+        let mut beamer_window: Option<BeamerWindow> = None;
 
-        event_loop.run(move |event, _, control_flow| match event {
+        event_loop.run(move |event, window_target, control_flow| match event {
             Event::NewEvents(_) => {
                 let now = Instant::now();
-                imgui.io_mut().update_delta_time(now - last_frame);
+                imgui
+                    .as_mut()
+                    .unwrap()
+                    .io_mut()
+                    .update_delta_time(now - last_frame);
                 last_frame = now;
             }
             Event::MainEventsCleared => {
+                // open or close the public display as requested by the user
+                let beamer_state = &mut self.program_state.as_mut().unwrap().beamer_state;
+                if beamer_state.open && beamer_window.is_none() {
+                    match BeamerWindow::open(window_target, &mut imgui) {
+                        Ok(window) => {
+                            beamer_window = Some(window);
+                            beamer_state.err_msg = None;
+                        }
+                        Err(msg) => {
+                            beamer_state.open = false;
+                            beamer_state.err_msg = Some(msg);
+                        }
+                    }
+                } else if !beamer_state.open {
+                    beamer_window = None;
+                }
+
                 let gl_window = display.gl_window();
                 platform
-                    .prepare_frame(imgui.io_mut(), gl_window.window())
+                    .prepare_frame(imgui.as_mut().unwrap().io_mut(), gl_window.window())
                     .expect("Failed to prepare frame");
                 gl_window.window().request_redraw();
+                if let Some(beamer_window) = beamer_window.as_ref() {
+                    beamer_window.request_redraw();
+                }
+            }
+            Event::RedrawRequested(window_id)
+                if beamer_window
+                    .as_ref()
+                    .map_or(false, |beamer_window| beamer_window.id() == window_id) =>
+            {
+                beamer_window
+                    .as_mut()
+                    .unwrap()
+                    .render(&mut imgui, self.program_state.as_mut().unwrap());
             }
             Event::RedrawRequested(_) => {
-                let mut ui = imgui.frame();
+                let mut ui = imgui.as_mut().unwrap().frame();
 
                 let mut run = true;
                 run_ui(
@@ -133,19 +173,9 @@ impl System {
                     self.program_state.as_mut().unwrap(),
                 );
                 if !run {
-                    // TODO: Is this wanted behavior?
-                    // wait for spawned threads to exit
+                    // running exports are cancelled instead of waited for, their threads end with the process
                     let program_state = self.program_state.as_mut().unwrap();
-
-                    program_state
-                        .competition
-                        .spawned_threads
-                        .drain(0..)
-                        .for_each(|handle| handle.join().expect("Thread panicked!"));
-
-                    // stop timer thread & autosave thread by dropping the value
-                    program_state.threads.autosave_guard = None;
-                    program_state.threads.timer = None;
+                    program_state.competition.export_jobs.cancel_all();
 
                     *control_flow = ControlFlow::Exit;
                 }
@@ -160,13 +190,24 @@ impl System {
                     .expect("Rendering failed");
                 target.finish().expect("Failed to swap buffers");
             }
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                window_id,
+            } if beamer_window
+                .as_ref()
+                .map_or(false, |beamer_window| beamer_window.id() == window_id) =>
+            {
+                // closing the public display does not close the program
+                beamer_window = None;
+                self.program_state.as_mut().unwrap().beamer_state.open = false;
+            }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
             } => *control_flow = ControlFlow::Exit,
             event => {
                 let gl_window = display.gl_window();
-                platform.handle_event(imgui.io_mut(), gl_window.window(), &event);
+                platform.handle_event(imgui.as_mut().unwrap().io_mut(), gl_window.window(), &event);
             }
         })
     }